@@ -1,46 +1,82 @@
 use anyhow::Result;
-use std::{fmt, ops::ControlFlow};
+use std::{
+    fmt,
+    ops::{ControlFlow, RangeInclusive},
+};
 
 use crate::environment::Environment;
 use crate::grammar::{Literal, Statement, Token};
-use crate::interpreter::Interpreter;
+use crate::interpreter::{Interpreter, Jump};
 
 pub trait LoxCallable: fmt::Debug {
-    fn arity(&self) -> usize;
+    fn name(&self) -> &str;
+    fn arity(&self) -> RangeInclusive<usize>;
     fn call(&self, interpreter: &mut Interpreter, arguments: &[Literal]) -> Result<Literal>;
     fn to_string(&self) -> String;
+    fn signature(&self) -> String;
+    fn annotations(&self) -> &[String];
 }
 
 #[derive(Debug, Clone)]
 pub enum Callable {
     Native {
-        arity: usize,
+        name: &'static str,
+        arity: RangeInclusive<usize>,
         call: fn(&mut Interpreter, &[Literal]) -> Result<Literal>,
     },
     Function(Function),
 }
 
 impl LoxCallable for Callable {
-    fn arity(&self) -> usize {
+    fn name(&self) -> &str {
         match self {
-            Callable::Native { arity, .. } => *arity,
-            Callable::Function(f) => f.params.len(),
+            Callable::Native { name, .. } => name,
+            Callable::Function(f) => &f.name.lexeme,
+        }
+    }
+
+    fn arity(&self) -> RangeInclusive<usize> {
+        match self {
+            Callable::Native { arity, .. } => arity.clone(),
+            Callable::Function(f) => f.params.len()..=f.params.len(),
         }
     }
 
     fn call(&self, interpreter: &mut Interpreter, arguments: &[Literal]) -> Result<Literal> {
         match self {
-            Callable::Native { call, .. } => call(interpreter, arguments),
+            Callable::Native { name, call, .. } => {
+                interpreter.native_called(name, arguments.len());
+                call(interpreter, arguments)
+            }
             Callable::Function(func) => func.execute(interpreter, arguments),
         }
     }
 
     fn to_string(&self) -> String {
         match self {
-            Callable::Native { .. } => format!("<native fn>"),
+            Callable::Native { .. } => "<native fn>".to_string(),
             Callable::Function(f) => format!("<fn {}>", f.name.lexeme),
         }
     }
+
+    fn signature(&self) -> String {
+        match self {
+            Callable::Native { name, arity, .. } if arity.start() == arity.end() => {
+                format!("native:{name}:{}", arity.start())
+            }
+            Callable::Native { name, arity, .. } => {
+                format!("native:{name}:{}-{}", arity.start(), arity.end())
+            }
+            Callable::Function(f) => format!("fn:{}:{}", f.name.lexeme, f.params.len()),
+        }
+    }
+
+    fn annotations(&self) -> &[String] {
+        match self {
+            Callable::Native { .. } => &[],
+            Callable::Function(f) => &f.annotations,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -48,20 +84,23 @@ pub struct Function {
     pub name: Token,
     params: Vec<Token>,
     body: Vec<Statement>,
+    annotations: Vec<String>,
     closure: Environment,
 }
 
 impl Function {
     pub fn new(
         name: &Token,
-        params: &Vec<Token>,
-        body: &Vec<Statement>,
+        params: &[Token],
+        body: &[Statement],
+        annotations: &[String],
         closure: &Environment,
     ) -> Self {
         Self {
             name: name.clone(),
-            params: params.clone(),
-            body: body.clone(),
+            params: params.to_vec(),
+            body: body.to_vec(),
+            annotations: annotations.to_vec(),
             closure: closure.clone(),
         }
     }
@@ -74,8 +113,16 @@ impl Function {
         }
         // Execute function body in the new environment
         match interpreter.execute_block(&self.body, env)? {
-            ControlFlow::Break(value) => Ok(value),
-            ControlFlow::Continue(()) => Ok(Literal::Nil),
+            ControlFlow::Break(Jump::Return(value)) => Ok(value),
+            ControlFlow::Break(_) | ControlFlow::Continue(()) => Ok(Literal::Nil),
         }
     }
 }
+
+pub fn describe_arity(arity: &RangeInclusive<usize>) -> String {
+    if arity.start() == arity.end() {
+        arity.start().to_string()
+    } else {
+        format!("{} to {}", arity.start(), arity.end())
+    }
+}
@@ -0,0 +1,311 @@
+use crate::grammar::{Expression, Literal, Statement};
+
+/// Renders a parsed program as a Graphviz DOT graph.
+pub fn to_dot(statements: &[Statement]) -> String {
+    let mut graph = DotGraph::default();
+    let root = graph.node("Program");
+    for statement in statements {
+        let child = graph.statement(statement);
+        graph.edge(root, child);
+    }
+    graph.finish()
+}
+
+#[derive(Default)]
+struct DotGraph {
+    lines: Vec<String>,
+    next_id: usize,
+}
+
+impl DotGraph {
+    fn node(&mut self, label: &str) -> usize {
+        let id = self.next_id;
+        self.next_id += 1;
+        let label = label.replace('\\', "\\\\").replace('"', "\\\"");
+        self.lines.push(format!("  n{id} [label=\"{label}\"];"));
+        id
+    }
+
+    fn edge(&mut self, from: usize, to: usize) {
+        self.lines.push(format!("  n{from} -> n{to};"));
+    }
+
+    fn labeled_edge(&mut self, from: usize, to: usize, label: &str) {
+        self.lines
+            .push(format!("  n{from} -> n{to} [label=\"{label}\"];"));
+    }
+
+    fn finish(self) -> String {
+        format!("digraph AST {{\n{}\n}}\n", self.lines.join("\n"))
+    }
+
+    fn statements(&mut self, parent: usize, statements: &[Statement]) {
+        for statement in statements {
+            let child = self.statement(statement);
+            self.edge(parent, child);
+        }
+    }
+
+    fn statement(&mut self, statement: &Statement) -> usize {
+        match statement {
+            Statement::Block(statements) => {
+                let id = self.node("Block");
+                self.statements(id, statements);
+                id
+            }
+            Statement::Expression(expr) => {
+                let id = self.node("Expression");
+                let child = self.expression(expr);
+                self.edge(id, child);
+                id
+            }
+            Statement::If {
+                condition,
+                then_branch,
+                else_branch,
+                ..
+            } => {
+                let id = self.node("If");
+                let child = self.expression(condition);
+                self.labeled_edge(id, child, "condition");
+                let child = self.statement(then_branch);
+                self.labeled_edge(id, child, "then");
+                if let Some(else_branch) = else_branch {
+                    let child = self.statement(else_branch);
+                    self.labeled_edge(id, child, "else");
+                }
+                id
+            }
+            Statement::IfChain {
+                branches,
+                else_branch,
+            } => {
+                let id = self.node("IfChain");
+                for (_, condition, branch) in branches {
+                    let child = self.expression(condition);
+                    self.labeled_edge(id, child, "condition");
+                    let child = self.statement(branch);
+                    self.labeled_edge(id, child, "then");
+                }
+                if let Some(else_branch) = else_branch {
+                    let child = self.statement(else_branch);
+                    self.labeled_edge(id, child, "else");
+                }
+                id
+            }
+            Statement::Print { value: expr, .. } => {
+                let id = self.node("Print");
+                let child = self.expression(expr);
+                self.edge(id, child);
+                id
+            }
+            Statement::VarGroup(declarations) => {
+                let id = self.node("VarGroup");
+                self.statements(id, declarations);
+                id
+            }
+            Statement::Variable { name, init } => {
+                let id = self.node(&format!("Var {}", name.lexeme));
+                if let Some(init) = init {
+                    let child = self.expression(init);
+                    self.edge(id, child);
+                }
+                id
+            }
+            Statement::While {
+                condition,
+                body,
+                increment,
+                ..
+            } => {
+                let id = self.node("While");
+                let child = self.expression(condition);
+                self.labeled_edge(id, child, "condition");
+                let child = self.statement(body);
+                self.labeled_edge(id, child, "body");
+                if let Some(increment) = increment {
+                    let child = self.expression(increment);
+                    self.labeled_edge(id, child, "increment");
+                }
+                id
+            }
+            Statement::Repeat { count, body, .. } => {
+                let id = self.node("Repeat");
+                let child = self.expression(count);
+                self.labeled_edge(id, child, "count");
+                let child = self.statement(body);
+                self.labeled_edge(id, child, "body");
+                id
+            }
+            Statement::RepeatUntil {
+                body, condition, ..
+            } => {
+                let id = self.node("RepeatUntil");
+                let child = self.statement(body);
+                self.labeled_edge(id, child, "body");
+                let child = self.expression(condition);
+                self.labeled_edge(id, child, "until");
+                id
+            }
+            Statement::ForIn {
+                name,
+                iterable,
+                body,
+                ..
+            } => {
+                let id = self.node(&format!("For {}", name.lexeme));
+                let child = self.expression(iterable);
+                self.labeled_edge(id, child, "in");
+                let child = self.statement(body);
+                self.labeled_edge(id, child, "body");
+                id
+            }
+            Statement::Function {
+                name, params, body, ..
+            } => {
+                let params = params
+                    .iter()
+                    .map(|param| param.lexeme.as_str())
+                    .collect::<Vec<&str>>()
+                    .join(", ");
+                let id = self.node(&format!("Fun {}({params})", name.lexeme));
+                self.statements(id, body);
+                id
+            }
+            Statement::Return { value, .. } => {
+                let id = self.node("Return");
+                if let Some(value) = value {
+                    let child = self.expression(value);
+                    self.edge(id, child);
+                }
+                id
+            }
+            Statement::Import(path) => self.node(&format!("Import {}", path.lexeme)),
+            Statement::Export { declaration, .. } => {
+                let id = self.node("Export");
+                let child = self.statement(declaration);
+                self.edge(id, child);
+                id
+            }
+            Statement::Debugger(_) => self.node("Debugger"),
+            Statement::Break(_) => self.node("Break"),
+            Statement::Continue(_) => self.node("Continue"),
+        }
+    }
+
+    fn expression(&mut self, expr: &Expression) -> usize {
+        match expr {
+            Expression::Assign { name, value } => {
+                let id = self.node(&format!("Assign {}", name.lexeme));
+                let child = self.expression(value);
+                self.edge(id, child);
+                id
+            }
+            Expression::Binary { left, op, right } | Expression::Logical { left, op, right } => {
+                let id = self.node(&op.lexeme);
+                let child = self.expression(left);
+                self.edge(id, child);
+                let child = self.expression(right);
+                self.edge(id, child);
+                id
+            }
+            Expression::Call {
+                callee, arguments, ..
+            } => {
+                let id = self.node("Call");
+                let child = self.expression(callee);
+                self.labeled_edge(id, child, "callee");
+                for argument in arguments {
+                    let child = self.expression(argument);
+                    self.edge(id, child);
+                }
+                id
+            }
+            Expression::Conditional {
+                condition,
+                then_branch,
+                else_branch,
+            } => {
+                let id = self.node("If");
+                let child = self.expression(condition);
+                self.labeled_edge(id, child, "condition");
+                let child = self.expression(then_branch);
+                self.labeled_edge(id, child, "then");
+                if let Some(else_branch) = else_branch {
+                    let child = self.expression(else_branch);
+                    self.labeled_edge(id, child, "else");
+                }
+                id
+            }
+            Expression::Grouping(expr) => {
+                let id = self.node("Group");
+                let child = self.expression(expr);
+                self.edge(id, child);
+                id
+            }
+            Expression::Index { object, index, .. } => {
+                let id = self.node("Index");
+                let child = self.expression(object);
+                self.labeled_edge(id, child, "object");
+                let child = self.expression(index);
+                self.labeled_edge(id, child, "index");
+                id
+            }
+            Expression::IndexSet {
+                object,
+                index,
+                value,
+                ..
+            } => {
+                let id = self.node("IndexSet");
+                let child = self.expression(object);
+                self.labeled_edge(id, child, "object");
+                let child = self.expression(index);
+                self.labeled_edge(id, child, "index");
+                let child = self.expression(value);
+                self.labeled_edge(id, child, "value");
+                id
+            }
+            Expression::ListLiteral(items) => {
+                let id = self.node("List");
+                for item in items {
+                    let child = self.expression(item);
+                    self.edge(id, child);
+                }
+                id
+            }
+            Expression::MapLiteral(entries) => {
+                let id = self.node("Map");
+                for (key, value) in entries {
+                    let child = self.expression(key);
+                    self.labeled_edge(id, child, "key");
+                    let child = self.expression(value);
+                    self.labeled_edge(id, child, "value");
+                }
+                id
+            }
+            Expression::Literal(literal) => self.node(&literal.to_string()),
+            Expression::Sequence(expressions) => {
+                let id = self.node(",");
+                for expr in expressions {
+                    let child = self.expression(expr);
+                    self.edge(id, child);
+                }
+                id
+            }
+            Expression::Unary { op, right } => {
+                let id = self.node(&op.lexeme);
+                let child = self.expression(right);
+                self.edge(id, child);
+                id
+            }
+            Expression::Unit { value, unit } => {
+                let id = self.node(&format!("Unit {}", unit.lexeme));
+                let child = self.node(&Literal::Number(*value).to_string());
+                self.edge(id, child);
+                id
+            }
+            Expression::Variable(name) => self.node(&format!("Var {}", name.lexeme)),
+        }
+    }
+}
@@ -33,6 +33,20 @@ impl Environment {
         self.inner.borrow().get(token)
     }
 
+    /// Removes a variable from this scope only, returning its value.
+    pub fn remove(&self, name: &str) -> Option<Literal> {
+        self.inner.borrow_mut().scope.remove(name)
+    }
+
+    /// Looks up a variable by name, returning `None` if it is undefined.
+    pub fn lookup(&self, name: &str) -> Option<Literal> {
+        let inner = self.inner.borrow();
+        match inner.scope.get(name) {
+            Some(value) => Some(value.clone()),
+            None => inner.enclosing.as_ref()?.lookup(name),
+        }
+    }
+
     pub fn assign(&self, token: &Token, value: &Literal) -> Result<()> {
         self.inner.borrow_mut().assign(token, value)
     }
@@ -58,7 +72,7 @@ impl EnvironmentImpl {
 
     pub fn get(&self, token: &Token) -> Result<Literal> {
         if let Some(value) = self.scope.get(&token.lexeme) {
-            return Ok(value.clone());
+            Ok(value.clone())
         } else if let Some(enclosing) = &self.enclosing {
             enclosing.get(token)
         } else {
@@ -1,7 +1,14 @@
+use std::ops::RangeInclusive;
 use thiserror::Error;
 
+use crate::callable::describe_arity;
+
 #[derive(Error, Debug)]
 pub enum RuntimeError {
+    #[error("Could not scan source.")]
+    ScannerError,
+    #[error("[line {line}] Error: {message}")]
+    PreprocessorError { line: usize, message: String },
     #[error("[line {}] Error at '{}': {}", line, lexeme, message)]
     ParserError {
         line: usize,
@@ -12,6 +19,25 @@ pub enum RuntimeError {
     TypeError(String),
     #[error("Undefined variable '{lexeme}'.\n[line {line}]")]
     UndefinedVariable { lexeme: String, line: usize },
-    #[error("Expected {expected} arguments but got {got}.")]
-    ArgumentCountError { expected: usize, got: usize },
+    #[error("Unknown unit '{lexeme}'.\n[line {line}]")]
+    UnknownUnit { lexeme: String, line: usize },
+    #[error(
+        "Expected {} arguments but got {got} in call to '{callee}'.",
+        describe_arity(expected)
+    )]
+    ArgumentCountError {
+        callee: String,
+        expected: RangeInclusive<usize>,
+        got: usize,
+    },
+    #[error("{message}\n[line {line}]")]
+    IndexError { message: String, line: usize },
+    #[error("{0}")]
+    AssertionFailed(String),
+    #[error("Could not import '{path}': {message}\n[line {line}]")]
+    ImportError {
+        path: String,
+        message: String,
+        line: usize,
+    },
 }
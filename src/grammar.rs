@@ -1,15 +1,19 @@
-use std::{fmt, rc::Rc};
+use std::{cell::RefCell, cmp::Ordering, collections::HashMap, fmt, rc::Rc};
 
 use crate::callable::LoxCallable;
 
 #[derive(Debug, Clone, PartialEq, strum::Display)]
-#[allow(non_camel_case_types)]
+#[allow(non_camel_case_types, clippy::upper_case_acronyms)]
 pub enum TokenType {
     LEFT_PAREN,
     RIGHT_PAREN,
     LEFT_BRACE,
     RIGHT_BRACE,
+    LEFT_BRACKET,
+    RIGHT_BRACKET,
 
+    AT,
+    COLON,
     COMMA,
     DOT,
     MINUS,
@@ -26,25 +30,39 @@ pub enum TokenType {
     LESS_EQUAL,
     GREATER,
     GREATER_EQUAL,
+    DOT_DOT,
+    DOT_DOT_EQUAL,
+    QUESTION_QUESTION_EQUAL,
 
     IDENTIFIER,
     STRING,
     NUMBER,
 
     AND,
+    AND_EQUAL,
+    BREAK,
     CLASS,
+    CONTINUE,
+    DEBUGGER,
     ELSE,
+    EXPORT,
     FALSE,
     FOR,
     FUN,
     IF,
+    IMPORT,
+    IN,
     NIL,
     OR,
+    OR_EQUAL,
     PRINT,
+    REPEAT,
     RETURN,
     SUPER,
+    THEN,
     THIS,
     TRUE,
+    UNTIL,
     VAR,
     WHILE,
 
@@ -55,19 +73,28 @@ impl TokenType {
     pub fn get_token_type(identifier: &str) -> Self {
         match identifier {
             "and" => Self::AND,
+            "break" => Self::BREAK,
             "class" => Self::CLASS,
+            "continue" => Self::CONTINUE,
+            "debugger" => Self::DEBUGGER,
             "else" => Self::ELSE,
+            "export" => Self::EXPORT,
             "false" => Self::FALSE,
             "for" => Self::FOR,
             "fun" => Self::FUN,
             "if" => Self::IF,
+            "import" => Self::IMPORT,
+            "in" => Self::IN,
             "nil" => Self::NIL,
             "or" => Self::OR,
             "print" => Self::PRINT,
+            "repeat" => Self::REPEAT,
             "return" => Self::RETURN,
             "super" => Self::SUPER,
+            "then" => Self::THEN,
             "this" => Self::THIS,
             "true" => Self::TRUE,
+            "until" => Self::UNTIL,
             "var" => Self::VAR,
             "while" => Self::WHILE,
             _ => Self::IDENTIFIER,
@@ -83,6 +110,15 @@ pub struct Token {
     pub line: usize,
 }
 
+impl Token {
+    /// Describes the token along with its source line, e.g.
+    /// `IDENTIFIER foo null (line 3)`. `Display` stays in the format the
+    /// `tokenize` command prints.
+    pub fn describe(&self) -> String {
+        format!("{self} (line {})", self.line)
+    }
+}
+
 impl fmt::Display for Token {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let literal = match &self.literal {
@@ -99,7 +135,15 @@ pub enum Literal {
     Boolean(bool),
     String(String),
     Number(f64),
+    /// `start..end`, or `start..=end` when inclusive. Iterates in steps of 1.
+    Range {
+        start: f64,
+        end: f64,
+        inclusive: bool,
+    },
     Callable(Rc<dyn LoxCallable>),
+    List(Rc<RefCell<Vec<Literal>>>),
+    Map(Rc<RefCell<HashMap<MapKey, Literal>>>),
 }
 
 impl Literal {
@@ -110,18 +154,185 @@ impl Literal {
             _ => true, // Everything else is truthy, including empty strings
         }
     }
+
+    /// Formats a value for program output. `Display` keeps the `.0` on
+    /// integers as the tokenizer and AST printer expect, while output
+    /// drops it: `5`, `2.5`, `-3`.
+    pub fn stringify(&self) -> String {
+        match self {
+            // Also matches -0.0
+            Literal::Number(0.0) => "0".to_string(),
+            Literal::Number(n) => n.to_string(),
+            Literal::List(_) | Literal::Map(_) => {
+                format_container(self, &Literal::stringify, &mut vec![])
+            }
+            _ => self.to_string(),
+        }
+    }
+
+    /// The map key for this value, or `None` if it can't be one. NaN is
+    /// never equal to itself, so it couldn't be looked up again.
+    pub fn map_key(&self) -> Option<MapKey> {
+        match self {
+            Literal::String(s) => Some(MapKey::String(s.clone())),
+            Literal::Number(n) if !n.is_nan() => Some(MapKey::Number((n + 0.0).to_bits())),
+            _ => None,
+        }
+    }
+}
+
+/// A key in a map. Numbers and strings are separate keys, so `m[1]` and
+/// `m["1"]` are different entries. A number is stored as its bits, with
+/// `-0` folded into `0` since the two compare equal.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum MapKey {
+    Number(u64),
+    String(String),
+}
+
+impl MapKey {
+    pub fn to_literal(&self) -> Literal {
+        match self {
+            MapKey::Number(bits) => Literal::Number(f64::from_bits(*bits)),
+            MapKey::String(s) => Literal::String(s.clone()),
+        }
+    }
+}
+
+/// Numbers sort before strings, numbers by value and strings by their
+/// characters, so maps print and iterate in a predictable order.
+impl Ord for MapKey {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (MapKey::Number(a), MapKey::Number(b)) => {
+                f64::from_bits(*a).total_cmp(&f64::from_bits(*b))
+            }
+            (MapKey::Number(_), MapKey::String(_)) => Ordering::Less,
+            (MapKey::String(_), MapKey::Number(_)) => Ordering::Greater,
+            (MapKey::String(a), MapKey::String(b)) => a.cmp(b),
+        }
+    }
+}
+
+impl PartialOrd for MapKey {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Formats a list or map, using `format_value` for everything inside it
+/// that isn't itself a list or map. Map entries are sorted by key, since
+/// `HashMap` order is arbitrary. `visiting` holds the containers being
+/// formatted further up, so one that holds itself prints as `[...]` or
+/// `{...}` instead of recursing forever.
+pub(crate) fn format_container(
+    value: &Literal,
+    format_value: &dyn Fn(&Literal) -> String,
+    visiting: &mut Vec<usize>,
+) -> String {
+    match value {
+        Literal::List(items) => {
+            let id = Rc::as_ptr(items) as usize;
+            if visiting.contains(&id) {
+                return "[...]".to_string();
+            }
+            visiting.push(id);
+            let items = items
+                .borrow()
+                .iter()
+                .map(|item| format_container(item, format_value, visiting))
+                .collect::<Vec<String>>()
+                .join(", ");
+            visiting.pop();
+            format!("[{items}]")
+        }
+        Literal::Map(entries) => {
+            let id = Rc::as_ptr(entries) as usize;
+            if visiting.contains(&id) {
+                return "{...}".to_string();
+            }
+            visiting.push(id);
+            let entries = entries.borrow();
+            let mut entries: Vec<(&MapKey, &Literal)> = entries.iter().collect();
+            entries.sort_by(|a, b| a.0.cmp(b.0));
+            let entries = entries
+                .into_iter()
+                .map(|(key, value)| {
+                    let key = format_value(&key.to_literal());
+                    format!("{key}: {}", format_container(value, format_value, visiting))
+                })
+                .collect::<Vec<String>>()
+                .join(", ");
+            visiting.pop();
+            format!("{{{entries}}}")
+        }
+        value => format_value(value),
+    }
 }
 
 impl PartialEq for Literal {
     fn eq(&self, other: &Self) -> bool {
-        match (self, other) {
-            (Literal::Nil, Literal::Nil) => true,
-            (Literal::Boolean(a), Literal::Boolean(b)) => a == b,
-            (Literal::String(a), Literal::String(b)) => a == b,
-            (Literal::Number(a), Literal::Number(b)) => a == b,
-            (Literal::Callable(a), Literal::Callable(b)) => Rc::ptr_eq(a, b),
-            _ => false,
+        structural_eq(self, other, &mut vec![])
+    }
+}
+
+/// Compares lists and maps by contents and everything else by value, except
+/// functions, which compare by identity. `visiting` holds the container
+/// pairs already being compared further up, so a cycle counts as equal
+/// instead of recursing forever.
+fn structural_eq(a: &Literal, b: &Literal, visiting: &mut Vec<(usize, usize)>) -> bool {
+    match (a, b) {
+        (Literal::List(a), Literal::List(b)) => {
+            let pair = (Rc::as_ptr(a) as usize, Rc::as_ptr(b) as usize);
+            if Rc::ptr_eq(a, b) || visiting.contains(&pair) {
+                return true;
+            }
+            let (a, b) = (a.borrow(), b.borrow());
+            if a.len() != b.len() {
+                return false;
+            }
+            visiting.push(pair);
+            let equal = a
+                .iter()
+                .zip(b.iter())
+                .all(|(a, b)| structural_eq(a, b, visiting));
+            visiting.pop();
+            equal
+        }
+        (Literal::Map(a), Literal::Map(b)) => {
+            let pair = (Rc::as_ptr(a) as usize, Rc::as_ptr(b) as usize);
+            if Rc::ptr_eq(a, b) || visiting.contains(&pair) {
+                return true;
+            }
+            let (a, b) = (a.borrow(), b.borrow());
+            if a.len() != b.len() {
+                return false;
+            }
+            visiting.push(pair);
+            let equal = a
+                .iter()
+                .all(|(key, a)| b.get(key).is_some_and(|b| structural_eq(a, b, visiting)));
+            visiting.pop();
+            equal
         }
+        (Literal::Nil, Literal::Nil) => true,
+        (Literal::Boolean(a), Literal::Boolean(b)) => a == b,
+        (Literal::String(a), Literal::String(b)) => a == b,
+        (Literal::Number(a), Literal::Number(b)) => a == b,
+        (
+            Literal::Range {
+                start: a_start,
+                end: a_end,
+                inclusive: a_inclusive,
+            },
+            Literal::Range {
+                start: b_start,
+                end: b_end,
+                inclusive: b_inclusive,
+            },
+        ) => a_start == b_start && a_end == b_end && a_inclusive == b_inclusive,
+        (Literal::Callable(a), Literal::Callable(b)) => Rc::ptr_eq(a, b),
+        _ => false,
     }
 }
 
@@ -130,6 +341,8 @@ impl fmt::Display for Literal {
         let output = match self {
             Literal::Boolean(b) => b.to_string(),
             Literal::String(s) => s.to_string(),
+            // Also matches -0.0, which prints the same as zero
+            Literal::Number(0.0) => "0.0".to_string(),
             Literal::Number(n) => {
                 if n.fract() == 0.0 {
                     // integer
@@ -140,7 +353,18 @@ impl fmt::Display for Literal {
                 }
             }
             Literal::Nil => "nil".to_string(),
+            Literal::Range {
+                start,
+                end,
+                inclusive,
+            } => {
+                let op = if *inclusive { "..=" } else { ".." };
+                format!("{start}{op}{end}")
+            }
             Literal::Callable(c) => c.to_string(),
+            Literal::List(_) | Literal::Map(_) => {
+                format_container(self, &Literal::to_string, &mut vec![])
+            }
         };
         write!(f, "{output}")
     }
@@ -159,22 +383,76 @@ pub enum Expression {
     },
     Call {
         callee: Box<Expression>,
+        paren: Token,
         arguments: Vec<Expression>,
     },
+    /// `if condition then a else b`. Without `else` it is nil when the
+    /// condition is falsy.
+    Conditional {
+        condition: Box<Expression>,
+        then_branch: Box<Expression>,
+        else_branch: Option<Box<Expression>>,
+    },
     Grouping(Box<Expression>),
+    Index {
+        object: Box<Expression>,
+        bracket: Token,
+        index: Box<Expression>,
+    },
+    IndexSet {
+        object: Box<Expression>,
+        bracket: Token,
+        index: Box<Expression>,
+        value: Box<Expression>,
+    },
+    ListLiteral(Vec<Expression>),
+    MapLiteral(Vec<(Expression, Expression)>),
     Literal(Literal),
     Logical {
         left: Box<Expression>,
         op: Token,
         right: Box<Expression>,
     },
+    Sequence(Vec<Expression>),
     Unary {
         op: Token,
         right: Box<Expression>,
     },
+    Unit {
+        value: f64,
+        unit: Token,
+    },
     Variable(Token),
 }
 
+impl Expression {
+    /// The line the expression starts on, if it contains any token.
+    pub fn line(&self) -> Option<usize> {
+        match self {
+            Expression::Assign { name, .. } => Some(name.line),
+            Expression::Binary { left, op, .. } | Expression::Logical { left, op, .. } => {
+                left.line().or(Some(op.line))
+            }
+            Expression::Call { callee, paren, .. } => callee.line().or(Some(paren.line)),
+            Expression::Conditional { condition, .. } => condition.line(),
+            Expression::Grouping(expr) => expr.line(),
+            Expression::Index {
+                object, bracket, ..
+            }
+            | Expression::IndexSet {
+                object, bracket, ..
+            } => object.line().or(Some(bracket.line)),
+            Expression::ListLiteral(items) => items.first()?.line(),
+            Expression::MapLiteral(entries) => entries.first()?.0.line(),
+            Expression::Literal(_) => None,
+            Expression::Sequence(expressions) => expressions.first()?.line(),
+            Expression::Unary { op, .. } => Some(op.line),
+            Expression::Unit { unit, .. } => Some(unit.line),
+            Expression::Variable(name) => Some(name.line),
+        }
+    }
+}
+
 impl fmt::Display for Expression {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -184,7 +462,9 @@ impl fmt::Display for Expression {
             Expression::Binary { left, op, right } => {
                 write!(f, "({} {} {})", op.lexeme, left, right)
             }
-            Expression::Call { callee, arguments } => {
+            Expression::Call {
+                callee, arguments, ..
+            } => {
                 let args = arguments
                     .iter()
                     .map(|arg| arg.to_string())
@@ -192,16 +472,58 @@ impl fmt::Display for Expression {
                     .join(", ");
                 write!(f, "(call {callee} {args})")
             }
+            Expression::Conditional {
+                condition,
+                then_branch,
+                else_branch: Some(else_branch),
+            } => write!(f, "(if {condition} {then_branch} {else_branch})"),
+            Expression::Conditional {
+                condition,
+                then_branch,
+                else_branch: None,
+            } => write!(f, "(if {condition} {then_branch})"),
             Expression::Grouping(g) => {
                 write!(f, "(group {g})")
             }
+            Expression::Index { object, index, .. } => write!(f, "(index {object} {index})"),
+            Expression::IndexSet {
+                object,
+                index,
+                value,
+                ..
+            } => write!(f, "(index-set {object} {index} {value})"),
+            Expression::ListLiteral(items) => {
+                let items = items
+                    .iter()
+                    .map(|item| format!(" {item}"))
+                    .collect::<String>();
+                write!(f, "(list{items})")
+            }
+            Expression::MapLiteral(entries) => {
+                let entries = entries
+                    .iter()
+                    .map(|(key, value)| format!(" {key} {value}"))
+                    .collect::<String>();
+                write!(f, "(map{entries})")
+            }
             Expression::Literal(l) => write!(f, "{l}"),
             Expression::Logical { left, op, right } => {
                 write!(f, "({} {} {})", op.lexeme, left, right)
             }
+            Expression::Sequence(expressions) => {
+                let expressions = expressions
+                    .iter()
+                    .map(|expr| expr.to_string())
+                    .collect::<Vec<String>>()
+                    .join(" ");
+                write!(f, "(, {expressions})")
+            }
             Expression::Unary { op, right } => {
                 write!(f, "({} {})", op.lexeme, right)
             }
+            Expression::Unit { value, unit } => {
+                write!(f, "({} {})", unit.lexeme, Literal::Number(*value))
+            }
             Expression::Variable(name) => write!(f, "(var {})", name.lexeme),
         }
     }
@@ -212,25 +534,113 @@ pub enum Statement {
     Block(Vec<Statement>),
     Expression(Expression),
     If {
+        keyword: Token,
         condition: Expression,
         then_branch: Box<Statement>,
         else_branch: Option<Box<Statement>>,
     },
-    Print(Expression),
+    /// An `if` followed by one or more `else if`s, kept flat so long chains
+    /// don't nest one level per branch. Each branch holds its `if` keyword.
+    IfChain {
+        branches: Vec<(Token, Expression, Statement)>,
+        else_branch: Option<Box<Statement>>,
+    },
+    Print {
+        keyword: Token,
+        value: Expression,
+    },
     Variable {
         name: Token,
         init: Option<Expression>,
     },
+    /// `var a = 1, b = 2;`, declared in order in the current scope.
+    VarGroup(Vec<Statement>),
     While {
+        keyword: Token,
         condition: Expression,
         body: Box<Statement>,
+        /// Run after each iteration of a desugared `for` loop.
+        increment: Option<Expression>,
+    },
+    Repeat {
+        keyword: Token,
+        count: Expression,
+        body: Box<Statement>,
+    },
+    /// `repeat body until (condition);` runs the body before each check,
+    /// so always at least once.
+    RepeatUntil {
+        keyword: Token,
+        body: Box<Statement>,
+        condition: Expression,
+    },
+    ForIn {
+        keyword: Token,
+        name: Token,
+        iterable: Expression,
+        body: Box<Statement>,
     },
     Function {
         name: Token,
         params: Vec<Token>,
         body: Vec<Statement>,
+        annotations: Vec<String>,
     },
     Return {
+        keyword: Token,
         value: Option<Expression>,
     },
+    Import(Token),
+    /// A top-level function or variable declaration that files importing
+    /// this one can see. It is defined in the global scope, so an exported
+    /// variable is one binding shared by the importer and the file itself.
+    Export {
+        keyword: Token,
+        declaration: Box<Statement>,
+    },
+    Debugger(Token),
+    Break(Token),
+    Continue(Token),
+}
+
+impl Statement {
+    /// The names this statement declares in the current scope.
+    pub fn declared_names(&self) -> Vec<&str> {
+        match self {
+            Statement::Variable { name, .. } | Statement::Function { name, .. } => {
+                vec![name.lexeme.as_str()]
+            }
+            Statement::VarGroup(declarations) => declarations
+                .iter()
+                .flat_map(Statement::declared_names)
+                .collect(),
+            Statement::Export { declaration, .. } => declaration.declared_names(),
+            _ => vec![],
+        }
+    }
+
+    /// The line the statement starts on. Blocks and exports have none of
+    /// their own, and neither does an expression statement made only of
+    /// literals.
+    pub fn line(&self) -> Option<usize> {
+        match self {
+            Statement::Block(_) | Statement::VarGroup(_) | Statement::Export { .. } => None,
+            Statement::Expression(expr) => expr.line(),
+            Statement::If { keyword, .. }
+            | Statement::Print { keyword, .. }
+            | Statement::While { keyword, .. }
+            | Statement::Repeat { keyword, .. }
+            | Statement::RepeatUntil { keyword, .. }
+            | Statement::Return { keyword, .. }
+            | Statement::ForIn { keyword, .. }
+            | Statement::Debugger(keyword)
+            | Statement::Break(keyword)
+            | Statement::Continue(keyword) => Some(keyword.line),
+            Statement::IfChain { branches, .. } => {
+                branches.first().map(|(keyword, ..)| keyword.line)
+            }
+            Statement::Import(path) => Some(path.line),
+            Statement::Variable { name, .. } | Statement::Function { name, .. } => Some(name.line),
+        }
+    }
 }
@@ -1,44 +1,292 @@
 use anyhow::Result;
 use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    fs,
+    io::{self, Write},
     ops::ControlFlow,
+    path::{Path, PathBuf},
     rc::Rc,
-    time::{SystemTime, UNIX_EPOCH},
 };
 
-use crate::callable::{Callable, Function};
+use crate::callable::{Callable, Function, LoxCallable};
 use crate::environment::Environment;
 use crate::error::RuntimeError;
 use crate::grammar::*;
+use crate::natives::{define_natives, system_clock};
+use crate::parser::Parser;
+use crate::preprocessor::preprocess;
+use crate::scanner::Scanner;
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Truthiness {
+    /// Only `nil` and `false` are falsy.
+    #[default]
+    Lox,
+    /// `0` and the empty string are falsy as well.
+    C,
+}
+
+type NativeCallHook = Box<dyn FnMut(&str, usize)>;
+
+/// Why a statement stopped running before reaching its end.
+#[derive(Debug)]
+pub enum Jump {
+    Return(Literal),
+    Break,
+    Continue,
+}
 
 pub struct Interpreter {
     pub env: Environment,
+    globals: Environment,
+    sandboxed: bool,
+    truthiness: Truthiness,
+    output: Box<dyn Write>,
+    base_dir: PathBuf,
+    imported: HashSet<PathBuf>,
+    units: HashMap<String, fn(f64) -> f64>,
+    hoist_functions: bool,
+    clock: fn() -> f64,
+    breakpoint: Option<Box<dyn FnMut(usize)>>,
+    call_line: usize,
+    coverage: Option<HashMap<usize, usize>>,
+    propagate_nil: bool,
+    native_call_hook: Option<NativeCallHook>,
+    features: HashSet<String>,
+}
+
+impl Default for Interpreter {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Interpreter {
     pub fn new() -> Self {
-        let clock_fn = Callable::Native {
-            arity: 0,
-            call: |_, _| {
-                let start = SystemTime::now();
-                let since_the_epoch = start.duration_since(UNIX_EPOCH).unwrap();
-                Ok(Literal::Number(since_the_epoch.as_secs_f64()))
-            },
-        };
+        Self::with_natives(false)
+    }
+
+    /// Creates an interpreter for untrusted scripts. Only these natives are
+    /// defined: `abs`, `annotations`, `assert`, `clock`, `compare`,
+    /// `deepEqual`, `floor`, `gcd`, `getOr`, `hash`, `insert`, `isDefined`,
+    /// `keys`, `lcm`, `len`, `now`, `parseFloat`, `parseInt`, `pop`, `product`,
+    /// `push`, `remove`, `repr`, `round`, `sign`, `signature`, `slice`, `sort`,
+    /// `str`, `sum`, `trunc` and `values`. None of them touch the host: `clock`
+    /// and `now` report the Unix epoch instead of the host's time. `import` is
+    /// rejected.
+    pub fn new_sandboxed() -> Self {
+        let mut interpreter = Self::with_natives(true);
+        interpreter.set_clock(|| 0.0);
+        interpreter
+    }
+
+    fn with_natives(sandboxed: bool) -> Self {
         let env = Environment::new();
-        env.define("clock", Literal::Callable(Rc::new(clock_fn)));
-        Interpreter { env }
+        define_natives(&env, sandboxed);
+        Interpreter {
+            env: env.clone(),
+            globals: env,
+            sandboxed,
+            truthiness: Truthiness::default(),
+            output: Box::new(io::stdout()),
+            base_dir: PathBuf::from("."),
+            imported: HashSet::new(),
+            units: HashMap::new(),
+            hoist_functions: false,
+            clock: system_clock,
+            breakpoint: None,
+            call_line: 0,
+            coverage: None,
+            propagate_nil: false,
+            native_call_hook: None,
+            features: HashSet::new(),
+        }
+    }
+
+    /// Discards all variables, functions and import history, and restores
+    /// the natives, keeping the sandbox's subset if sandboxed. Settings
+    /// such as the output writer, clock, sandboxing, truthiness and
+    /// registered units are kept.
+    pub fn reset(&mut self) {
+        let env = Environment::new();
+        define_natives(&env, self.sandboxed);
+        self.env = env.clone();
+        self.globals = env;
+        self.imported.clear();
+    }
+
+    /// Records the script being run, so `import` paths resolve relative to it
+    /// and the script itself is never imported again.
+    pub fn set_script_path(&mut self, path: impl AsRef<Path>) {
+        let path = path.as_ref();
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            self.base_dir = dir.to_path_buf();
+        }
+        if let Ok(path) = path.canonicalize() {
+            self.imported.insert(path);
+        }
+    }
+
+    /// Overrides the time source behind `clock` and `now`, in seconds since
+    /// the Unix epoch, e.g. to make timing-dependent scripts deterministic.
+    pub fn set_clock(&mut self, clock: fn() -> f64) {
+        self.clock = clock;
+    }
+
+    pub(crate) fn current_time(&self) -> f64 {
+        (self.clock)()
+    }
+
+    pub(crate) fn is_global_defined(&self, name: &str) -> bool {
+        self.globals.lookup(name).is_some()
+    }
+
+    /// The line of the most recent call, for natives to report errors at.
+    pub(crate) fn call_line(&self) -> usize {
+        self.call_line
+    }
+
+    /// Redirects the output of `print` statements, which defaults to stdout.
+    pub fn set_output(&mut self, output: impl Write + 'static) {
+        self.output = Box::new(output);
+    }
+
+    /// Registers a conversion applied to unit expressions like `5 seconds`,
+    /// which the parser only produces when `Parser::set_units` is enabled.
+    pub fn register_unit(&mut self, name: &str, convert: fn(f64) -> f64) {
+        self.units.insert(name.to_string(), convert);
+    }
+
+    /// When enabled, every function declared in a block is defined before
+    /// the block runs, so it can be called above its declaration.
+    pub fn set_hoist_functions(&mut self, hoist_functions: bool) {
+        self.hoist_functions = hoist_functions;
+    }
+
+    /// Registers a callback invoked with the line number each time a
+    /// `debugger;` statement runs. Without one, `debugger;` does nothing.
+    pub fn set_breakpoint(&mut self, breakpoint: impl FnMut(usize) + 'static) {
+        self.breakpoint = Some(Box::new(breakpoint));
+    }
+
+    /// Starts or stops counting how many times each line of the program
+    /// runs. Enabling it clears earlier counts.
+    pub fn set_coverage(&mut self, enabled: bool) {
+        self.coverage = enabled.then(HashMap::new);
+    }
+
+    /// Hit counts per line of the programs run since coverage was enabled.
+    /// Lines holding statements that never ran are present with a count of
+    /// zero. Imported files are not tracked.
+    pub fn coverage(&self) -> HashMap<usize, usize> {
+        self.coverage.clone().unwrap_or_default()
+    }
+
+    /// When enabled, arithmetic with a `nil` operand evaluates to `nil`
+    /// instead of raising a type error, so `nil + 5` is `nil`.
+    pub fn set_propagate_nil(&mut self, propagate_nil: bool) {
+        self.propagate_nil = propagate_nil;
+    }
+
+    /// Registers a callback invoked with the name and argument count of
+    /// every native function call, e.g. to profile which natives run.
+    pub fn on_native_call(&mut self, hook: impl FnMut(&str, usize) + 'static) {
+        self.native_call_hook = Some(Box::new(hook));
+    }
+
+    pub(crate) fn native_called(&mut self, name: &str, arg_count: usize) {
+        if let Some(hook) = self.native_call_hook.as_mut() {
+            hook(name, arg_count);
+        }
+    }
+
+    /// Enables `@if FEATURE` blocks in imported files. The CLI passes the
+    /// same features to `preprocess` for the script itself.
+    pub fn define_feature(&mut self, feature: &str) {
+        self.features.insert(feature.to_string());
+    }
+
+    pub fn set_truthiness(&mut self, truthiness: Truthiness) {
+        self.truthiness = truthiness;
+    }
+
+    pub(crate) fn is_truthy(&self, value: &Literal) -> bool {
+        match (self.truthiness, value) {
+            (Truthiness::C, Literal::Number(n)) => *n != 0.0,
+            (Truthiness::C, Literal::String(s)) => !s.is_empty(),
+            _ => value.is_truthy(),
+        }
     }
 
     pub fn interpret(&mut self, statements: &[Statement]) -> Result<Literal> {
-        for statement in statements.iter() {
-            if let ControlFlow::Break(rv) = self.execute(statement)? {
-                return Ok(rv);
+        if let Some(coverage) = &mut self.coverage {
+            track_lines(statements, coverage);
+        }
+        match self.execute_statements(statements)? {
+            ControlFlow::Break(Jump::Return(rv)) => Ok(rv),
+            // The parser rejects `break` and `continue` outside of loops
+            ControlFlow::Break(_) | ControlFlow::Continue(()) => Ok(Literal::Nil),
+        }
+    }
+
+    /// Runs a program like `interpret`, and also returns the value of the
+    /// last statement if it is an expression statement.
+    pub fn interpret_with_result(&mut self, statements: &[Statement]) -> Result<Option<Literal>> {
+        match statements.split_last() {
+            Some((last @ Statement::Expression(expr), rest)) => {
+                if let Some(coverage) = &mut self.coverage {
+                    track_lines(statements, coverage);
+                }
+                if let ControlFlow::Break(_) = self.execute_statements(rest)? {
+                    return Ok(None);
+                }
+                self.record_hit(last);
+                Ok(Some(self.evaluate(expr)?))
+            }
+            _ => {
+                self.interpret(statements)?;
+                Ok(None)
             }
         }
-        Ok(Literal::Nil)
     }
 
-    pub fn execute(&mut self, statement: &Statement) -> Result<ControlFlow<Literal>> {
+    /// Scans, parses and evaluates a single expression against the current
+    /// scope, e.g. for watch expressions. Between runs that is the global
+    /// scope, so locals of a finished function are no longer visible.
+    pub fn eval_expression(&mut self, source: &str) -> Result<Literal> {
+        let mut scanner = Scanner::new(source);
+        let tokens = scanner.scan_tokens();
+        if scanner.error {
+            return Err(RuntimeError::ScannerError.into());
+        }
+        let expr = Parser::new(&tokens).parse_expression("Expect end of expression.")?;
+        self.evaluate(&expr)
+    }
+
+    /// Calls the global `main` function if the program defined one, and
+    /// returns its result. A `main` with one parameter gets `args` as a list
+    /// of strings.
+    pub fn call_main(&mut self, args: &[String]) -> Result<Option<Literal>> {
+        let Some(main) = self.globals.lookup("main") else {
+            return Ok(None);
+        };
+        let Literal::Callable(main) = main else {
+            return Err(self.type_error("'main' must be a function."));
+        };
+        let arguments = if main.arity().contains(&1) {
+            let args = args.iter().cloned().map(Literal::String).collect();
+            vec![Literal::List(Rc::new(RefCell::new(args)))]
+        } else if main.arity().contains(&0) {
+            vec![]
+        } else {
+            return Err(self.type_error("'main' must take no parameters or one list of arguments."));
+        };
+        self.call(&main, &arguments).map(Some)
+    }
+
+    pub fn execute(&mut self, statement: &Statement) -> Result<ControlFlow<Jump>> {
+        self.record_hit(statement);
         match statement {
             Statement::Block(statements) => {
                 let env = Environment::new_enclosed(&self.env);
@@ -52,8 +300,10 @@ impl Interpreter {
                 condition,
                 then_branch,
                 else_branch,
+                ..
             } => {
-                if self.evaluate(condition)?.is_truthy() {
+                let condition = self.evaluate(condition)?;
+                if self.is_truthy(&condition) {
                     self.execute(then_branch)
                 } else if let Some(else_branch) = else_branch {
                     self.execute(else_branch)
@@ -61,10 +311,34 @@ impl Interpreter {
                     Ok(ControlFlow::Continue(()))
                 }
             }
-            Statement::Print(expr) => {
-                match self.evaluate(expr)? {
-                    Literal::Number(n) => println!("{n}"),
-                    val => println!("{val}"),
+            Statement::IfChain {
+                branches,
+                else_branch,
+            } => {
+                for (index, (keyword, condition, branch)) in branches.iter().enumerate() {
+                    // The first branch was counted as the chain itself
+                    if index > 0 {
+                        self.record_line(keyword.line);
+                    }
+                    let condition = self.evaluate(condition)?;
+                    if self.is_truthy(&condition) {
+                        return self.execute(branch);
+                    }
+                }
+                match else_branch {
+                    Some(else_branch) => self.execute(else_branch),
+                    None => Ok(ControlFlow::Continue(())),
+                }
+            }
+            Statement::Print { value: expr, .. } => {
+                let value = self.evaluate(expr)?;
+                writeln!(self.output, "{}", value.stringify())?;
+                Ok(ControlFlow::Continue(()))
+            }
+            Statement::VarGroup(declarations) => {
+                for declaration in declarations {
+                    // Declarations never break out of the block
+                    let _ = self.execute(declaration)?;
                 }
                 Ok(ControlFlow::Continue(()))
             }
@@ -77,27 +351,159 @@ impl Interpreter {
                 self.env.define(&name.lexeme, value);
                 Ok(ControlFlow::Continue(()))
             }
-            Statement::While { condition, body } => {
-                while self.evaluate(condition)?.is_truthy() {
-                    if let ControlFlow::Break(rv) = self.execute(body)? {
-                        return Ok(ControlFlow::Break(rv));
+            Statement::While {
+                condition,
+                body,
+                increment,
+                ..
+            } => {
+                loop {
+                    let condition = self.evaluate(condition)?;
+                    if !self.is_truthy(&condition) {
+                        break;
+                    }
+                    match self.execute(body)? {
+                        ControlFlow::Break(Jump::Break) => break,
+                        ControlFlow::Break(jump @ Jump::Return(_)) => {
+                            return Ok(ControlFlow::Break(jump))
+                        }
+                        ControlFlow::Break(Jump::Continue) | ControlFlow::Continue(()) => (),
+                    }
+                    if let Some(increment) = increment {
+                        self.evaluate(increment)?;
+                    }
+                }
+                Ok(ControlFlow::Continue(()))
+            }
+            Statement::Repeat { count, body, .. } => {
+                let count = match self.evaluate(count)? {
+                    Literal::Number(n) if n >= 0.0 && n.fract() == 0.0 => n as u64,
+                    _ => {
+                        return Err(self.type_error("Repeat count must be a non-negative integer."))
+                    }
+                };
+                for _ in 0..count {
+                    match self.execute(body)? {
+                        ControlFlow::Break(Jump::Break) => break,
+                        ControlFlow::Break(jump @ Jump::Return(_)) => {
+                            return Ok(ControlFlow::Break(jump))
+                        }
+                        ControlFlow::Break(Jump::Continue) | ControlFlow::Continue(()) => (),
+                    }
+                }
+                Ok(ControlFlow::Continue(()))
+            }
+            Statement::RepeatUntil {
+                body, condition, ..
+            } => {
+                loop {
+                    match self.execute(body)? {
+                        ControlFlow::Break(Jump::Break) => break,
+                        ControlFlow::Break(jump @ Jump::Return(_)) => {
+                            return Ok(ControlFlow::Break(jump))
+                        }
+                        ControlFlow::Break(Jump::Continue) | ControlFlow::Continue(()) => (),
+                    }
+                    let condition = self.evaluate(condition)?;
+                    if self.is_truthy(&condition) {
+                        break;
+                    }
+                }
+                Ok(ControlFlow::Continue(()))
+            }
+            Statement::ForIn {
+                name,
+                iterable,
+                body,
+                ..
+            } => {
+                let values: Box<dyn Iterator<Item = Literal>> = match self.evaluate(iterable)? {
+                    Literal::Range {
+                        start,
+                        end,
+                        inclusive,
+                    } => {
+                        // Past 2^53 adding 1 stops changing the value, so the
+                        // loop would never end
+                        if !(start.abs() <= MAX_SAFE_INTEGER && end.abs() <= MAX_SAFE_INTEGER) {
+                            return Err(self.type_error(
+                                "Range bounds must be within 2^53 - 1 of zero to iterate.",
+                            ));
+                        }
+                        Box::new(
+                            std::iter::successors(Some(start), |value| Some(value + 1.0))
+                                .take_while(move |value| {
+                                    *value < end || (inclusive && *value == end)
+                                })
+                                .map(Literal::Number),
+                        )
+                    }
+                    // Iterates over a snapshot, so the body may modify the list
+                    Literal::List(items) => Box::new(items.borrow().clone().into_iter()),
+                    Literal::Map(entries) => {
+                        let mut keys: Vec<MapKey> = entries.borrow().keys().cloned().collect();
+                        keys.sort();
+                        Box::new(keys.into_iter().map(|key| key.to_literal()))
+                    }
+                    _ => {
+                        return Err(self.type_error("Can only iterate over ranges, lists and maps."))
+                    }
+                };
+                for value in values {
+                    // A fresh scope per iteration, so closures capture each value
+                    let env = Environment::new_enclosed(&self.env);
+                    env.define(&name.lexeme, value);
+                    match self.execute_block(std::slice::from_ref(body), env)? {
+                        ControlFlow::Break(Jump::Break) => break,
+                        ControlFlow::Break(jump @ Jump::Return(_)) => {
+                            return Ok(ControlFlow::Break(jump))
+                        }
+                        ControlFlow::Break(Jump::Continue) | ControlFlow::Continue(()) => (),
                     }
                 }
                 Ok(ControlFlow::Continue(()))
             }
-            Statement::Function { name, params, body } => {
-                let func = Function::new(name, params, body, &self.env);
+            Statement::Function {
+                name,
+                params,
+                body,
+                annotations,
+            } => {
+                let func = Function::new(name, params, body, annotations, &self.env);
                 let func_literal = Literal::Callable(Rc::new(Callable::Function(func)));
                 self.env.define(&name.lexeme, func_literal);
                 Ok(ControlFlow::Continue(()))
             }
-            Statement::Return { value } => {
+            Statement::Return { value, .. } => {
                 let rv = if let Some(expr) = value {
                     self.evaluate(expr)?
                 } else {
                     Literal::Nil
                 };
-                Ok(ControlFlow::Break(rv))
+                Ok(ControlFlow::Break(Jump::Return(rv)))
+            }
+            Statement::Break(_) => Ok(ControlFlow::Break(Jump::Break)),
+            Statement::Continue(_) => Ok(ControlFlow::Break(Jump::Continue)),
+            Statement::Import(path) => {
+                self.import(path)?;
+                Ok(ControlFlow::Continue(()))
+            }
+            Statement::Export { declaration, .. } => {
+                let flow = self.execute(declaration)?;
+                // Moving the binding to the globals lets the importer and the
+                // file's own code share it, while its other names stay private
+                for name in declaration.declared_names() {
+                    if let Some(value) = self.env.remove(name) {
+                        self.globals.define(name, value);
+                    }
+                }
+                Ok(flow)
+            }
+            Statement::Debugger(keyword) => {
+                if let Some(breakpoint) = self.breakpoint.as_mut() {
+                    breakpoint(keyword.line);
+                }
+                Ok(ControlFlow::Continue(()))
             }
         }
     }
@@ -112,6 +518,16 @@ impl Interpreter {
             Expression::Binary { left, op, right } => {
                 let left = self.evaluate(left)?;
                 let right = self.evaluate(right)?;
+                let is_arithmetic = matches!(
+                    op.token_type,
+                    TokenType::STAR | TokenType::SLASH | TokenType::PLUS | TokenType::MINUS
+                );
+                if self.propagate_nil
+                    && is_arithmetic
+                    && (left == Literal::Nil || right == Literal::Nil)
+                {
+                    return Ok(Literal::Nil);
+                }
                 match op.token_type {
                     TokenType::STAR => match (left, right) {
                         (Literal::Number(l), Literal::Number(r)) => Literal::Number(l * r),
@@ -141,12 +557,52 @@ impl Interpreter {
                         }
                         _ => return Err(self.type_error("Operands must be numbers.")),
                     },
+                    TokenType::DOT_DOT | TokenType::DOT_DOT_EQUAL => match (left, right) {
+                        (Literal::Number(start), Literal::Number(end)) => Literal::Range {
+                            start,
+                            end,
+                            inclusive: op.token_type == TokenType::DOT_DOT_EQUAL,
+                        },
+                        _ => return Err(self.type_error("Range bounds must be numbers.")),
+                    },
+                    TokenType::IN => match (left, right) {
+                        (
+                            Literal::Number(n),
+                            Literal::Range {
+                                start,
+                                end,
+                                inclusive,
+                            },
+                        ) => {
+                            // Only the values iteration would produce are in the range
+                            let in_bounds = n >= start && (n < end || (inclusive && n == end));
+                            Literal::Boolean(in_bounds && (n - start).fract() == 0.0)
+                        }
+                        (_, Literal::Range { .. }) => Literal::Boolean(false),
+                        (value, Literal::List(items)) => {
+                            Literal::Boolean(items.borrow().contains(&value))
+                        }
+                        (key, Literal::Map(entries)) => {
+                            let key = self.map_key(&key)?;
+                            Literal::Boolean(entries.borrow().contains_key(&key))
+                        }
+                        _ => {
+                            return Err(self
+                                .type_error("Right operand of 'in' must be a range, list or map."))
+                        }
+                    },
                     TokenType::EQUAL_EQUAL => Literal::Boolean(left == right),
                     TokenType::BANG_EQUAL => Literal::Boolean(left != right),
                     _ => todo!(),
                 }
             }
-            Expression::Call { callee, arguments } => {
+            Expression::Call {
+                callee,
+                paren,
+                arguments,
+            } => {
+                // A call spanning several lines reports where it starts
+                let line = callee.line().unwrap_or(paren.line);
                 let callee = self.evaluate(callee)?;
                 let Literal::Callable(callee) = callee else {
                     return Err(self.type_error("Can only call functions and classes."));
@@ -155,23 +611,92 @@ impl Interpreter {
                     .iter()
                     .map(|arg| self.evaluate(arg))
                     .collect::<Result<Vec<Literal>>>()?;
-                if args.len() != callee.arity() {
-                    let err = RuntimeError::ArgumentCountError {
-                        expected: callee.arity(),
-                        got: args.len(),
-                    };
-                    return Err(err.into());
+                self.call_line = line;
+                self.call(&callee, &args)?
+            }
+            Expression::Conditional {
+                condition,
+                then_branch,
+                else_branch,
+            } => {
+                let condition = self.evaluate(condition)?;
+                if self.is_truthy(&condition) {
+                    self.evaluate(then_branch)?
+                } else if let Some(else_branch) = else_branch {
+                    self.evaluate(else_branch)?
+                } else {
+                    Literal::Nil
                 }
-                callee.call(self, &args)?
             }
             Expression::Grouping(expr) => self.evaluate(expr)?,
+            Expression::Index {
+                object,
+                bracket,
+                index,
+            } => {
+                let object = self.evaluate(object)?;
+                let index = self.evaluate(index)?;
+                match object {
+                    Literal::List(items) => {
+                        let items = items.borrow();
+                        items[list_index(&index, items.len(), bracket)?].clone()
+                    }
+                    // A missing key reads as nil
+                    Literal::Map(entries) => {
+                        let key = self.map_key(&index)?;
+                        entries.borrow().get(&key).cloned().unwrap_or(Literal::Nil)
+                    }
+                    _ => return Err(self.type_error("Only lists and maps can be indexed.")),
+                }
+            }
+            Expression::IndexSet {
+                object,
+                bracket,
+                index,
+                value,
+            } => {
+                let object = self.evaluate(object)?;
+                let index = self.evaluate(index)?;
+                let value = self.evaluate(value)?;
+                match object {
+                    Literal::List(items) => {
+                        let mut items = items.borrow_mut();
+                        let index = list_index(&index, items.len(), bracket)?;
+                        items[index] = value.clone();
+                    }
+                    Literal::Map(entries) => {
+                        let key = self.map_key(&index)?;
+                        entries.borrow_mut().insert(key, value.clone());
+                    }
+                    _ => return Err(self.type_error("Only lists and maps can be indexed.")),
+                }
+                value
+            }
+            Expression::ListLiteral(items) => {
+                let items = items
+                    .iter()
+                    .map(|item| self.evaluate(item))
+                    .collect::<Result<Vec<Literal>>>()?;
+                Literal::List(Rc::new(RefCell::new(items)))
+            }
+            Expression::MapLiteral(entries) => {
+                let mut map = HashMap::new();
+                for (key, value) in entries {
+                    let key = self.evaluate(key)?;
+                    let key = self.map_key(&key)?;
+                    let value = self.evaluate(value)?;
+                    map.insert(key, value);
+                }
+                Literal::Map(Rc::new(RefCell::new(map)))
+            }
             Expression::Literal(l) => l.clone(),
             Expression::Logical { left, op, right } => {
                 let left = self.evaluate(left)?;
-                let left_truthy = left.is_truthy();
+                let left_truthy = self.is_truthy(&left);
                 let eval_right = match op.token_type {
-                    TokenType::OR => !left_truthy,
-                    TokenType::AND => left_truthy,
+                    TokenType::OR | TokenType::OR_EQUAL => !left_truthy,
+                    TokenType::AND | TokenType::AND_EQUAL => left_truthy,
+                    TokenType::QUESTION_QUESTION_EQUAL => left == Literal::Nil,
                     _ => unreachable!(),
                 };
                 if eval_right {
@@ -180,17 +705,35 @@ impl Interpreter {
                     left
                 }
             }
+            Expression::Sequence(expressions) => {
+                let mut value = Literal::Nil;
+                for expr in expressions {
+                    value = self.evaluate(expr)?;
+                }
+                value
+            }
             Expression::Unary { op, right } => {
                 let literal = self.evaluate(right)?;
                 match op.token_type {
-                    TokenType::BANG => Literal::Boolean(!literal.is_truthy()),
+                    TokenType::BANG => Literal::Boolean(!self.is_truthy(&literal)),
                     TokenType::MINUS => match literal {
                         Literal::Number(n) => Literal::Number(-n),
+                        Literal::Nil if self.propagate_nil => Literal::Nil,
                         _ => return Err(self.type_error("Operand must be a number.")),
                     },
                     _ => unreachable!(),
                 }
             }
+            Expression::Unit { value, unit } => {
+                let Some(convert) = self.units.get(&unit.lexeme) else {
+                    let err = RuntimeError::UnknownUnit {
+                        lexeme: unit.lexeme.clone(),
+                        line: unit.line,
+                    };
+                    return Err(err.into());
+                };
+                Literal::Number(convert(*value))
+            }
             Expression::Variable(var) => self.env.get(var)?,
         };
         Ok(literal)
@@ -200,23 +743,189 @@ impl Interpreter {
         &mut self,
         statements: &[Statement],
         env: Environment,
-    ) -> Result<ControlFlow<Literal>> {
+    ) -> Result<ControlFlow<Jump>> {
         let previous_env = std::mem::replace(&mut self.env, env);
+        let result = self.execute_statements(statements);
+        // Restore the outer scope even if the block errored
+        self.env = previous_env;
+        result
+    }
+
+    fn execute_statements(&mut self, statements: &[Statement]) -> Result<ControlFlow<Jump>> {
+        let is_function = |statement: &&Statement| match statement {
+            Statement::Export { declaration, .. } => {
+                matches!(**declaration, Statement::Function { .. })
+            }
+            statement => matches!(statement, Statement::Function { .. }),
+        };
+        if self.hoist_functions {
+            for statement in statements.iter().filter(is_function) {
+                // Declaring a function never breaks out of the block
+                let _ = self.execute(statement)?;
+            }
+        }
         for statement in statements {
+            if self.hoist_functions && is_function(&statement) {
+                continue;
+            }
             if let ControlFlow::Break(rv) = self.execute(statement)? {
-                self.env = previous_env;
                 return Ok(ControlFlow::Break(rv));
             }
         }
-        self.env = previous_env;
         Ok(ControlFlow::Continue(()))
     }
 
+    fn import(&mut self, path: &Token) -> Result<()> {
+        let Some(Literal::String(relative_path)) = &path.literal else {
+            unreachable!()
+        };
+        let import_error = |message: String| RuntimeError::ImportError {
+            path: relative_path.clone(),
+            message,
+            line: path.line,
+        };
+        if self.sandboxed {
+            return Err(import_error("imports are disabled in sandbox mode".to_string()).into());
+        }
+
+        let full_path = self.base_dir.join(relative_path);
+        let source = fs::read_to_string(&full_path).map_err(|e| import_error(e.to_string()))?;
+        let full_path = full_path
+            .canonicalize()
+            .map_err(|e| import_error(e.to_string()))?;
+        if !self.imported.insert(full_path.clone()) {
+            // Already imported, possibly through an import cycle
+            return Ok(());
+        }
+
+        let source =
+            preprocess(&source, &self.features).map_err(|e| import_error(e.to_string()))?;
+        let mut scanner = Scanner::new(&source);
+        let tokens = scanner.scan_tokens();
+        if scanner.error {
+            return Err(import_error("could not scan source".to_string()).into());
+        }
+        let statements = Parser::new(&tokens).parse()?;
+        // Only exported declarations reach the globals
+        let env = Environment::new_enclosed(&self.globals);
+
+        // Nested imports are relative to the imported file
+        let dir = full_path.parent().unwrap_or(Path::new(".")).to_path_buf();
+        let previous_dir = std::mem::replace(&mut self.base_dir, dir);
+        // Coverage is keyed by line, which would mix up the two files
+        let coverage = self.coverage.take();
+        let result = self.execute_block(&statements, env);
+        self.coverage = coverage;
+        self.base_dir = previous_dir;
+        // A top-level return only ends the imported file
+        result.map(|_| ())
+    }
+
+    fn record_hit(&mut self, statement: &Statement) {
+        if let Some(line) = statement.line() {
+            self.record_line(line);
+        }
+    }
+
+    fn record_line(&mut self, line: usize) {
+        if let Some(coverage) = &mut self.coverage {
+            *coverage.entry(line).or_default() += 1;
+        }
+    }
+
+    pub(crate) fn call(
+        &mut self,
+        callee: &Rc<dyn LoxCallable>,
+        args: &[Literal],
+    ) -> Result<Literal> {
+        if !callee.arity().contains(&args.len()) {
+            let err = RuntimeError::ArgumentCountError {
+                callee: callee.name().to_string(),
+                expected: callee.arity(),
+                got: args.len(),
+            };
+            return Err(err.into());
+        }
+        callee.call(self, args)
+    }
+
+    fn map_key(&self, key: &Literal) -> Result<MapKey> {
+        key.map_key()
+            .ok_or_else(|| self.type_error("Map keys must be strings or numbers."))
+    }
+
     fn type_error(&self, message: &str) -> anyhow::Error {
         RuntimeError::TypeError(message.to_string()).into()
     }
 }
 
+/// Checks that `index` is a whole number within a list of length `len`.
+/// Negative indices count back from the end, so `-1` is the last item.
+fn list_index(index: &Literal, len: usize, bracket: &Token) -> Result<usize> {
+    let index_error = |message: &str| RuntimeError::IndexError {
+        message: message.to_string(),
+        line: bracket.line,
+    };
+    match index {
+        Literal::Number(n) if n.fract() == 0.0 => {
+            let index = if *n < 0.0 { n + len as f64 } else { *n };
+            if (0.0..len as f64).contains(&index) {
+                Ok(index as usize)
+            } else {
+                Err(index_error("List index out of range.").into())
+            }
+        }
+        _ => Err(index_error("List index must be an integer.").into()),
+    }
+}
+
+/// Adds every line holding a statement to `coverage` with no hits yet.
+fn track_lines(statements: &[Statement], coverage: &mut HashMap<usize, usize>) {
+    for statement in statements {
+        if let Some(line) = statement.line() {
+            coverage.entry(line).or_default();
+        }
+        match statement {
+            Statement::Block(body)
+            | Statement::VarGroup(body)
+            | Statement::Function { body, .. } => track_lines(body, coverage),
+            Statement::Export { declaration, .. } => {
+                track_lines(std::slice::from_ref(declaration), coverage)
+            }
+            Statement::If {
+                then_branch,
+                else_branch,
+                ..
+            } => {
+                track_lines(std::slice::from_ref(then_branch), coverage);
+                if let Some(else_branch) = else_branch {
+                    track_lines(std::slice::from_ref(else_branch), coverage);
+                }
+            }
+            Statement::IfChain {
+                branches,
+                else_branch,
+            } => {
+                for (keyword, _, branch) in branches {
+                    coverage.entry(keyword.line).or_default();
+                    track_lines(std::slice::from_ref(branch), coverage);
+                }
+                if let Some(else_branch) = else_branch {
+                    track_lines(std::slice::from_ref(else_branch), coverage);
+                }
+            }
+            Statement::While { body, .. }
+            | Statement::Repeat { body, .. }
+            | Statement::RepeatUntil { body, .. }
+            | Statement::ForIn { body, .. } => track_lines(std::slice::from_ref(body), coverage),
+            _ => (),
+        }
+    }
+}
+
+/// Every integer up to this magnitude is exactly representable as an `f64`.
+const MAX_SAFE_INTEGER: f64 = 9_007_199_254_740_991.0;
+
 fn compare_number(op: &TokenType, l: f64, r: f64) -> bool {
     match op {
         TokenType::EQUAL_EQUAL => l == r,
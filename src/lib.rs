@@ -1,12 +1,61 @@
 mod callable;
+mod dot;
 mod environment;
 mod error;
 mod grammar;
 mod interpreter;
+mod natives;
 mod parser;
+mod preprocessor;
 mod scanner;
 
-pub use grammar::Literal;
-pub use interpreter::Interpreter;
+pub use dot::to_dot;
+pub use error::RuntimeError;
+pub use grammar::{Literal, Token, TokenType};
+pub use interpreter::{Interpreter, Jump, Truthiness};
 pub use parser::Parser;
+pub use preprocessor::preprocess;
 pub use scanner::Scanner;
+
+use anyhow::Result;
+use std::{cell::RefCell, io, rc::Rc};
+
+/// Scans `source` without printing errors. Returns the tokens and whether
+/// any scan error occurred.
+pub fn tokenize(source: &str) -> (Vec<Token>, bool) {
+    let mut scanner = Scanner::new(source);
+    scanner.set_quiet(true);
+    let tokens = scanner.scan_tokens();
+    (tokens, scanner.error)
+}
+
+/// Runs a whole program and returns everything it printed.
+pub fn interpret_to_string(source: &str) -> Result<String> {
+    let mut scanner = Scanner::new(source);
+    let tokens = scanner.scan_tokens();
+    if scanner.error {
+        return Err(RuntimeError::ScannerError.into());
+    }
+    let statements = Parser::new(&tokens).parse()?;
+
+    let buffer = SharedBuffer::default();
+    let mut interpreter = Interpreter::new();
+    interpreter.set_output(buffer.clone());
+    interpreter.interpret(&statements)?;
+
+    let output = buffer.0.borrow();
+    Ok(String::from_utf8_lossy(&output).into_owned())
+}
+
+#[derive(Clone, Default)]
+struct SharedBuffer(Rc<RefCell<Vec<u8>>>);
+
+impl io::Write for SharedBuffer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.borrow_mut().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
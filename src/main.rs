@@ -1,7 +1,9 @@
 use std::{
+    collections::HashSet,
     env, fs,
-    io::{self, Write},
+    io::{self, Read},
     process::exit,
+    time::{Duration, Instant},
 };
 
 use interpreter_starter_rust::*;
@@ -42,7 +44,9 @@ fn evaluate(input: &str) {
     }
 
     let mut parser = Parser::new(&tokens);
-    let expr = match parser.expression() {
+    let expr = match parser
+        .parse_expression("evaluate expects a single expression, found trailing tokens.")
+    {
         Ok(expr) => expr,
         Err(msg) => {
             eprintln!("{msg}");
@@ -52,18 +56,12 @@ fn evaluate(input: &str) {
 
     let mut interpreter = Interpreter::new();
     match interpreter.evaluate(&expr) {
-        Ok(val) => match val {
-            Literal::Number(n) => println!("{n}"),
-            _ => println!("{val}"),
-        },
-        Err(msg) => {
-            eprintln!("{msg}");
-            exit(70);
-        }
+        Ok(val) => println!("{}", val.stringify()),
+        Err(e) => handle_runtime_error(e),
     }
 }
 
-fn run(input: &str) {
+fn dump_dot(input: &str) {
     let mut scanner = Scanner::new(input);
     let tokens = scanner.scan_tokens();
     if scanner.error {
@@ -71,48 +69,195 @@ fn run(input: &str) {
     }
 
     let mut parser = Parser::new(&tokens);
-    let statements = match parser.parse() {
-        Ok(statements) => statements,
+    match parser.parse() {
+        Ok(statements) => print!("{}", to_dot(&statements)),
         Err(msg) => {
             eprintln!("{msg}");
             exit(65);
         }
+    }
+}
+
+#[derive(Default)]
+struct Options {
+    lenient: bool,
+    print_last: bool,
+    call_main: bool,
+    script_args: Vec<String>,
+    coverage: bool,
+    time: bool,
+    features: HashSet<String>,
+}
+
+/// Phase durations for `--time`, kept so they can be printed even when a
+/// later phase fails.
+struct Timings {
+    enabled: bool,
+    phases: Vec<(&'static str, Duration)>,
+}
+
+impl Timings {
+    fn time<T>(&mut self, phase: &'static str, f: impl FnOnce() -> T) -> T {
+        let start = Instant::now();
+        let result = f();
+        self.phases.push((phase, start.elapsed()));
+        result
+    }
+
+    fn print(&self) {
+        if self.enabled {
+            for (phase, duration) in &self.phases {
+                eprintln!("{phase}: {duration:?}");
+            }
+        }
+    }
+
+    fn exit(&self, code: i32) -> ! {
+        self.print();
+        exit(code)
+    }
+}
+
+fn run(input: &str, filename: &str, options: &Options) {
+    let mut timings = Timings {
+        enabled: options.time,
+        phases: vec![],
+    };
+
+    // Only `run` honours `@if` blocks; the other commands show the source as is
+    let input = match timings.time("preprocess", || preprocess(input, &options.features)) {
+        Ok(source) => source,
+        Err(e) => {
+            eprintln!("{e}");
+            timings.exit(65);
+        }
+    };
+
+    let mut scanner = Scanner::new(&input);
+    let tokens = timings.time("scan", || scanner.scan_tokens());
+    if scanner.error {
+        timings.exit(65);
+    }
+
+    let mut parser = Parser::new(&tokens);
+    parser.set_lenient(options.lenient);
+    let statements = match timings.time("parse", || parser.parse()) {
+        Ok(statements) => statements,
+        Err(msg) => {
+            eprintln!("{msg}");
+            timings.exit(65);
+        }
     };
 
     let mut interpreter = Interpreter::new();
-    if let Err(e) = interpreter.interpret(&statements) {
-        eprintln!("{e}");
-        exit(70);
+    interpreter.set_script_path(filename);
+    interpreter.set_coverage(options.coverage);
+    for feature in &options.features {
+        interpreter.define_feature(feature);
+    }
+    let result = timings.time("interpret", || -> anyhow::Result<()> {
+        let last = interpreter.interpret_with_result(&statements)?;
+        if let Some(value) = last.filter(|_| options.print_last) {
+            println!("{}", value.stringify());
+        }
+        if options.call_main {
+            interpreter.call_main(&options.script_args)?;
+        }
+        Ok(())
+    });
+    if let Err(e) = result {
+        timings.print();
+        handle_runtime_error(e);
+    }
+
+    timings.print();
+    if options.coverage {
+        print_coverage(&interpreter);
+    }
+}
+
+fn print_coverage(interpreter: &Interpreter) {
+    let mut lines: Vec<(usize, usize)> = interpreter.coverage().into_iter().collect();
+    lines.sort_unstable();
+    let run = lines.iter().filter(|(_, hits)| *hits > 0).count();
+    eprintln!("Coverage: {run} of {} lines run", lines.len());
+    for (line, hits) in lines {
+        eprintln!("line {line}: {hits}");
+    }
+}
+
+fn handle_runtime_error(e: anyhow::Error) -> ! {
+    eprintln!("{e}");
+    match e.downcast_ref::<RuntimeError>() {
+        Some(RuntimeError::AssertionFailed(_)) => exit(71),
+        _ => exit(70),
     }
 }
 
 fn main() {
     let args: Vec<String> = env::args().collect();
     if args.len() < 3 {
-        writeln!(
-            io::stderr(),
-            "Usage: {} [tokenize|parse|evaluate|run] <filename>",
+        eprintln!(
+            "Usage: {} [tokenize|parse|evaluate|run|dump-dot] [--lenient] [--print-last] [--main] [--coverage] [--time] [--define FEATURE] <filename|-> [args...]",
             args[0]
-        )
-        .unwrap();
+        );
         return;
     }
 
     let command = &args[1];
-    let filename = &args[2];
-    let file_contents = fs::read_to_string(filename).unwrap_or_else(|_| {
-        writeln!(io::stderr(), "Failed to read file {filename}").unwrap();
+    let mut options = Options::default();
+    let mut filename = None;
+    let mut args_iter = args[2..].iter();
+    while let Some(arg) = args_iter.next() {
+        match arg.as_str() {
+            "--define" => {
+                let Some(feature) = args_iter.next() else {
+                    eprintln!("Missing feature name after --define");
+                    return;
+                };
+                options.features.insert(feature.clone());
+            }
+            "--lenient" => options.lenient = true,
+            "--print-last" => options.print_last = true,
+            "--main" => options.call_main = true,
+            "--coverage" => options.coverage = true,
+            "--time" => options.time = true,
+            flag if flag.starts_with("--") => {
+                eprintln!("Unknown option: {flag}");
+                return;
+            }
+            _ => {
+                // Whatever follows the filename is passed to `main`
+                filename = Some(arg);
+                options.script_args = args_iter.cloned().collect();
+                break;
+            }
+        }
+    }
+    let Some(filename) = filename else {
+        eprintln!("Missing filename");
+        return;
+    };
+
+    // `-` reads the program from stdin
+    let file_contents = if filename == "-" {
+        let mut source = String::new();
+        io::stdin().read_to_string(&mut source).map(|_| source)
+    } else {
+        fs::read_to_string(filename)
+    };
+    let file_contents = file_contents.unwrap_or_else(|_| {
+        eprintln!("Failed to read file {filename}");
         String::new()
     });
-
     match command.as_str() {
         "tokenize" => tokenize(&file_contents),
         "parse" => parse(&file_contents),
         "evaluate" => evaluate(&file_contents),
-        "run" => run(&file_contents),
+        "dump-dot" => dump_dot(&file_contents),
+        "run" => run(&file_contents, filename, &options),
         _ => {
-            writeln!(io::stderr(), "Unknown command: {command}").unwrap();
-            return;
+            eprintln!("Unknown command: {command}");
         }
     }
 }
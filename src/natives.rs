@@ -0,0 +1,638 @@
+use anyhow::Result;
+use std::{
+    cell::RefCell,
+    cmp::Ordering,
+    collections::HashMap,
+    hash::{DefaultHasher, Hash, Hasher},
+    ops::RangeInclusive,
+    rc::Rc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::callable::Callable;
+use crate::environment::Environment;
+use crate::error::RuntimeError;
+use crate::grammar::{format_container, Literal, MapKey};
+use crate::interpreter::Interpreter;
+
+type NativeFn = fn(&mut Interpreter, &[Literal]) -> Result<Literal>;
+
+/// The natives a sandboxed interpreter defines. A native is only exposed to
+/// untrusted scripts once it is added here.
+const SANDBOX_NATIVES: &[&str] = &[
+    "abs",
+    "annotations",
+    "assert",
+    "clock",
+    "compare",
+    "deepEqual",
+    "floor",
+    "gcd",
+    "getOr",
+    "hash",
+    "insert",
+    "isDefined",
+    "keys",
+    "lcm",
+    "len",
+    "now",
+    "parseFloat",
+    "parseInt",
+    "pop",
+    "product",
+    "push",
+    "remove",
+    "repr",
+    "round",
+    "sign",
+    "signature",
+    "slice",
+    "sort",
+    "str",
+    "sum",
+    "trunc",
+    "values",
+];
+
+pub fn define_natives(env: &Environment, sandboxed: bool) {
+    if sandboxed {
+        let all = Environment::new();
+        define_natives(&all, false);
+        for name in SANDBOX_NATIVES {
+            if let Some(native) = all.lookup(name) {
+                env.define(name, native);
+            }
+        }
+        return;
+    }
+    define(env, "abs", 1..=1, abs);
+    define(env, "annotations", 1..=1, annotations);
+    define(env, "assert", 1..=1, assert);
+    define(env, "clock", 0..=0, clock);
+    define(env, "compare", 2..=2, compare);
+    define(env, "deepEqual", 2..=2, deep_equal);
+    define(env, "floor", 1..=1, floor);
+    define(env, "gcd", 2..=2, gcd);
+    define(env, "getOr", 3..=3, get_or);
+    define(env, "hash", 1..=1, hash);
+    define(env, "insert", 3..=3, insert);
+    define(env, "isDefined", 1..=1, is_defined);
+    define(env, "keys", 1..=1, keys);
+    define(env, "lcm", 2..=2, lcm);
+    define(env, "len", 1..=1, len);
+    define(env, "now", 0..=0, now);
+    define(env, "parseFloat", 1..=1, parse_float);
+    define(env, "parseInt", 1..=2, parse_int);
+    define(env, "pop", 1..=1, pop);
+    define(env, "product", 1..=1, product);
+    define(env, "push", 2..=2, push);
+    define(env, "remove", 2..=2, remove);
+    define(env, "repr", 1..=1, repr);
+    define(env, "round", 1..=2, round);
+    define(env, "sign", 1..=1, sign);
+    define(env, "signature", 1..=1, signature);
+    define(env, "slice", 3..=4, slice);
+    define(env, "sort", 1..=2, sort);
+    define(env, "str", 1..=1, str);
+    define(env, "sum", 1..=1, sum);
+    define(env, "trunc", 1..=1, trunc);
+    define(env, "values", 1..=1, values);
+}
+fn define(env: &Environment, name: &'static str, arity: RangeInclusive<usize>, call: NativeFn) {
+    let native = Callable::Native { name, arity, call };
+    env.define(name, Literal::Callable(Rc::new(native)));
+}
+
+pub fn system_clock() -> f64 {
+    let since_the_epoch = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+    since_the_epoch.as_secs_f64()
+}
+
+fn clock(interpreter: &mut Interpreter, _: &[Literal]) -> Result<Literal> {
+    Ok(Literal::Number(interpreter.current_time()))
+}
+
+fn now(interpreter: &mut Interpreter, _: &[Literal]) -> Result<Literal> {
+    let secs = interpreter.current_time().max(0.0) as u64;
+    Ok(Literal::String(format_timestamp(secs)))
+}
+
+/// Formats seconds since the Unix epoch as `YYYY-MM-DDThh:mm:ssZ`.
+fn format_timestamp(secs: u64) -> String {
+    let (days, secs) = (secs / 86400, secs % 86400);
+    let (hour, minute, second) = (secs / 3600, secs % 3600 / 60, secs % 60);
+
+    // Convert days since 1970-01-01 to a civil date, with years starting in March
+    let z = days + 719468;
+    let era = z / 146097;
+    let day_of_era = z % 146097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + u64::from(month <= 2);
+
+    format!("{year:04}-{month:02}-{day:02}T{hour:02}:{minute:02}:{second:02}Z")
+}
+
+fn assert(interpreter: &mut Interpreter, args: &[Literal]) -> Result<Literal> {
+    if interpreter.is_truthy(&args[0]) {
+        Ok(Literal::Nil)
+    } else {
+        let message = format!("[line {}] Assertion failed.", interpreter.call_line());
+        Err(RuntimeError::AssertionFailed(message).into())
+    }
+}
+
+fn abs(_: &mut Interpreter, args: &[Literal]) -> Result<Literal> {
+    Ok(Literal::Number(number(&args[0])?.abs()))
+}
+
+fn sign(_: &mut Interpreter, args: &[Literal]) -> Result<Literal> {
+    let x = number(&args[0])?;
+    let sign = if x > 0.0 {
+        1.0
+    } else if x < 0.0 {
+        -1.0
+    } else {
+        0.0
+    };
+    Ok(Literal::Number(sign))
+}
+
+fn compare(_: &mut Interpreter, args: &[Literal]) -> Result<Literal> {
+    let ordering = match (&args[0], &args[1]) {
+        (Literal::Number(a), Literal::Number(b)) => a
+            .partial_cmp(b)
+            .ok_or_else(|| type_error("Cannot compare NaN."))?,
+        (Literal::String(a), Literal::String(b)) => a.cmp(b),
+        _ => return Err(type_error("Arguments must be two numbers or two strings.")),
+    };
+    Ok(Literal::Number(ordering as i8 as f64))
+}
+
+/// Same as `==`, which already compares lists and maps by contents and
+/// stops at cycles; kept as a native so the intent reads at the call site.
+fn deep_equal(_: &mut Interpreter, args: &[Literal]) -> Result<Literal> {
+    Ok(Literal::Boolean(args[0] == args[1]))
+}
+
+fn sum(_: &mut Interpreter, args: &[Literal]) -> Result<Literal> {
+    fold_numbers(&args[0], 0.0, |total, n| total + n)
+}
+
+fn product(_: &mut Interpreter, args: &[Literal]) -> Result<Literal> {
+    fold_numbers(&args[0], 1.0, |total, n| total * n)
+}
+
+/// Folds a list that must hold only numbers, starting from `init`.
+fn fold_numbers(value: &Literal, init: f64, f: impl Fn(f64, f64) -> f64) -> Result<Literal> {
+    list(value)?
+        .borrow()
+        .iter()
+        .try_fold(init, |total, item| match item {
+            Literal::Number(n) => Ok(f(total, *n)),
+            _ => Err(type_error("List items must be numbers.")),
+        })
+        .map(Literal::Number)
+}
+
+fn gcd(_: &mut Interpreter, args: &[Literal]) -> Result<Literal> {
+    let (a, b) = (integer(&args[0])?, integer(&args[1])?);
+    Ok(Literal::Number(greatest_common_divisor(a, b) as f64))
+}
+
+fn lcm(_: &mut Interpreter, args: &[Literal]) -> Result<Literal> {
+    let (a, b) = (integer(&args[0])?, integer(&args[1])?);
+    if a == 0 || b == 0 {
+        return Ok(Literal::Number(0.0));
+    }
+    let lcm = u128::from(a / greatest_common_divisor(a, b)) * u128::from(b);
+    Ok(Literal::Number(lcm as f64))
+}
+
+fn greatest_common_divisor(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+fn floor(_: &mut Interpreter, args: &[Literal]) -> Result<Literal> {
+    Ok(Literal::Number(number(&args[0])?.floor()))
+}
+
+fn round(_: &mut Interpreter, args: &[Literal]) -> Result<Literal> {
+    let x = number(&args[0])?;
+    let digits = match args.get(1) {
+        Some(Literal::Number(d)) if *d >= 0.0 && d.fract() == 0.0 => *d as i32,
+        Some(_) => return Err(type_error("Digits must be a non-negative integer.")),
+        None => 0,
+    };
+    let factor = 10f64.powi(digits);
+    let scaled = x * factor;
+    // Past this point a float has no more digits to round away
+    if !factor.is_finite() || !scaled.is_finite() {
+        return Ok(Literal::Number(x));
+    }
+    Ok(Literal::Number(scaled.round() / factor))
+}
+
+fn trunc(_: &mut Interpreter, args: &[Literal]) -> Result<Literal> {
+    Ok(Literal::Number(number(&args[0])?.trunc()))
+}
+
+/// Returns a new list of the function's annotation names, in source order.
+fn annotations(_: &mut Interpreter, args: &[Literal]) -> Result<Literal> {
+    let Literal::Callable(callable) = &args[0] else {
+        return Err(type_error("Only functions have annotations."));
+    };
+    let names = callable
+        .annotations()
+        .iter()
+        .map(|name| Literal::String(name.clone()))
+        .collect();
+    Ok(Literal::List(Rc::new(RefCell::new(names))))
+}
+
+/// Checks the global scope only, so locals are never reported as defined.
+fn is_defined(interpreter: &mut Interpreter, args: &[Literal]) -> Result<Literal> {
+    let name = string(&args[0])?;
+    Ok(Literal::Boolean(interpreter.is_global_defined(name)))
+}
+
+fn signature(_: &mut Interpreter, args: &[Literal]) -> Result<Literal> {
+    match &args[0] {
+        Literal::Callable(callable) => Ok(Literal::String(callable.signature())),
+        _ => Err(type_error("Argument must be a function.")),
+    }
+}
+
+fn str(_: &mut Interpreter, args: &[Literal]) -> Result<Literal> {
+    Ok(Literal::String(args[0].stringify()))
+}
+
+/// Like `str`, but strings are quoted and escaped, and numbers keep full
+/// precision, so values of different types never look alike.
+fn repr(_: &mut Interpreter, args: &[Literal]) -> Result<Literal> {
+    Ok(Literal::String(represent(&args[0])))
+}
+
+fn represent(value: &Literal) -> String {
+    match value {
+        Literal::String(s) => {
+            let mut quoted = String::from('"');
+            for c in s.chars() {
+                match c {
+                    '"' => quoted.push_str("\\\""),
+                    '\\' => quoted.push_str("\\\\"),
+                    '\n' => quoted.push_str("\\n"),
+                    '\r' => quoted.push_str("\\r"),
+                    '\t' => quoted.push_str("\\t"),
+                    c => quoted.push(c),
+                }
+            }
+            quoted.push('"');
+            quoted
+        }
+        Literal::Number(n) => format!("{n:?}"),
+        Literal::List(_) | Literal::Map(_) => format_container(value, &represent, &mut vec![]),
+        value => value.to_string(),
+    }
+}
+
+fn len(_: &mut Interpreter, args: &[Literal]) -> Result<Literal> {
+    match &args[0] {
+        Literal::String(s) => Ok(Literal::Number(s.chars().count() as f64)),
+        Literal::List(items) => Ok(Literal::Number(items.borrow().len() as f64)),
+        Literal::Map(entries) => Ok(Literal::Number(entries.borrow().len() as f64)),
+        _ => Err(type_error("Only strings, lists and maps have a length.")),
+    }
+}
+
+/// Returns the value stored under `key`, or `default` if the map has no
+/// such entry. A key stored with a nil value is still present.
+fn get_or(_: &mut Interpreter, args: &[Literal]) -> Result<Literal> {
+    let entries = map(&args[0])?;
+    let key = args[1]
+        .map_key()
+        .ok_or_else(|| type_error("Map keys must be strings or numbers."))?;
+    let value = entries.borrow().get(&key).cloned();
+    Ok(value.unwrap_or_else(|| args[2].clone()))
+}
+
+/// Returns the keys of a map in sorted order, or the indices of a list.
+fn keys(_: &mut Interpreter, args: &[Literal]) -> Result<Literal> {
+    let keys = match &args[0] {
+        Literal::Map(entries) => sorted_entries(&entries.borrow())
+            .into_iter()
+            .map(|(key, _)| key.to_literal())
+            .collect(),
+        Literal::List(items) => (0..items.borrow().len())
+            .map(|index| Literal::Number(index as f64))
+            .collect(),
+        _ => return Err(type_error("Argument must be a list or map.")),
+    };
+    Ok(Literal::List(Rc::new(RefCell::new(keys))))
+}
+
+/// Returns the values of a map ordered by their keys, or a copy of a list.
+fn values(_: &mut Interpreter, args: &[Literal]) -> Result<Literal> {
+    let values = match &args[0] {
+        Literal::Map(entries) => sorted_entries(&entries.borrow())
+            .into_iter()
+            .map(|(_, value)| value)
+            .collect(),
+        Literal::List(items) => items.borrow().clone(),
+        _ => return Err(type_error("Argument must be a list or map.")),
+    };
+    Ok(Literal::List(Rc::new(RefCell::new(values))))
+}
+
+fn sorted_entries(entries: &HashMap<MapKey, Literal>) -> Vec<(MapKey, Literal)> {
+    let mut entries: Vec<(MapKey, Literal)> = entries
+        .iter()
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect();
+    entries.sort_by(|a, b| a.0.cmp(&b.0));
+    entries
+}
+
+/// Returns a hash that is equal for equal values and the same on every
+/// run. Lists and maps hash by contents, like their equality, and functions
+/// by identity.
+fn hash(_: &mut Interpreter, args: &[Literal]) -> Result<Literal> {
+    let mut hasher = DefaultHasher::new();
+    let mut budget = HASH_BUDGET;
+    hash_value(&args[0], &mut hasher, &mut budget);
+    // Keep 53 bits so the hash is exact as a number
+    Ok(Literal::Number((hasher.finish() >> 11) as f64))
+}
+
+/// How many lists and maps `hash` looks inside before hashing the rest by
+/// type and length only.
+const HASH_BUDGET: usize = 256;
+
+/// Hashes containers as if they were fully unrolled, so a cycle hashes the
+/// same however it is written, just as `==` compares it. `budget` counts
+/// the containers still to look inside; past that they hash by type and
+/// length only, which keeps cycles finite.
+fn hash_value(value: &Literal, hasher: &mut DefaultHasher, budget: &mut usize) {
+    match value {
+        Literal::Nil => 0.hash(hasher),
+        Literal::Boolean(b) => (1, b).hash(hasher),
+        Literal::String(s) => (2, s).hash(hasher),
+        // `-0 == 0`, so both must hash alike
+        Literal::Number(n) => (3, (n + 0.0).to_bits()).hash(hasher),
+        Literal::Range {
+            start,
+            end,
+            inclusive,
+        } => (4, (start + 0.0).to_bits(), (end + 0.0).to_bits(), inclusive).hash(hasher),
+        Literal::Callable(callable) => (5, Rc::as_ptr(callable) as *const ()).hash(hasher),
+        Literal::List(items) => {
+            let items = items.borrow();
+            (6, items.len()).hash(hasher);
+            if *budget == 0 {
+                return;
+            }
+            *budget -= 1;
+            for item in items.iter() {
+                hash_value(item, hasher, budget);
+            }
+        }
+        Literal::Map(entries) => {
+            let entries = sorted_entries(&entries.borrow());
+            (7, entries.len()).hash(hasher);
+            if *budget == 0 {
+                return;
+            }
+            *budget -= 1;
+            for (key, value) in &entries {
+                key.hash(hasher);
+                hash_value(value, hasher, budget);
+            }
+        }
+    }
+}
+
+/// Appends `value` to the list in place.
+fn push(_: &mut Interpreter, args: &[Literal]) -> Result<Literal> {
+    list(&args[0])?.borrow_mut().push(args[1].clone());
+    Ok(Literal::Nil)
+}
+
+/// Removes and returns the last item of the list.
+fn pop(interpreter: &mut Interpreter, args: &[Literal]) -> Result<Literal> {
+    let popped = list(&args[0])?.borrow_mut().pop();
+    popped.ok_or_else(|| index_error(interpreter, "Cannot pop from an empty list."))
+}
+
+/// Inserts `value` before `index`, shifting later items along. An index
+/// equal to the length appends, and so does `-1`, since negative indices
+/// count back from there.
+fn insert(interpreter: &mut Interpreter, args: &[Literal]) -> Result<Literal> {
+    let mut items = list(&args[0])?.borrow_mut();
+    let index = position(interpreter, &args[1], items.len() + 1)?;
+    items.insert(index, args[2].clone());
+    Ok(Literal::Nil)
+}
+
+/// Removes and returns the item at `index`, shifting later items back.
+fn remove(interpreter: &mut Interpreter, args: &[Literal]) -> Result<Literal> {
+    let mut items = list(&args[0])?.borrow_mut();
+    let index = position(interpreter, &args[1], items.len())?;
+    Ok(items.remove(index))
+}
+
+/// Checks that `index` is a whole number below `len`, counting negative
+/// indices back from `len`.
+fn position(interpreter: &Interpreter, index: &Literal, len: usize) -> Result<usize> {
+    match index {
+        Literal::Number(n) if n.fract() == 0.0 => {
+            let index = if *n < 0.0 { n + len as f64 } else { *n };
+            if (0.0..len as f64).contains(&index) {
+                Ok(index as usize)
+            } else {
+                Err(index_error(interpreter, "List index out of range."))
+            }
+        }
+        _ => Err(index_error(interpreter, "List index must be an integer.")),
+    }
+}
+
+fn index_error(interpreter: &Interpreter, message: &str) -> anyhow::Error {
+    RuntimeError::IndexError {
+        message: message.to_string(),
+        line: interpreter.call_line(),
+    }
+    .into()
+}
+
+/// Returns a new list of the items from `start` up to but not including
+/// `end`, taking every `step`th one. Negative bounds count back from the
+/// end of the list.
+fn slice(interpreter: &mut Interpreter, args: &[Literal]) -> Result<Literal> {
+    let items = list(&args[0])?.borrow();
+    let index_error = |message: &str| index_error(interpreter, message);
+    let bound = |value: &Literal| match value {
+        Literal::Number(n) if n.fract() == 0.0 => {
+            let bound = if *n < 0.0 { n + items.len() as f64 } else { *n };
+            if (0.0..=items.len() as f64).contains(&bound) {
+                Ok(bound as usize)
+            } else {
+                Err(index_error("Slice bound out of range."))
+            }
+        }
+        _ => Err(index_error("Slice bounds must be integers.")),
+    };
+    let (start, end) = (bound(&args[1])?, bound(&args[2])?);
+    let step = match args.get(3) {
+        Some(Literal::Number(n)) if *n >= 1.0 && n.fract() == 0.0 => *n as usize,
+        Some(_) => return Err(index_error("Slice step must be a positive integer.")),
+        None => 1,
+    };
+    let sliced = items
+        .get(start..end.max(start))
+        .unwrap_or_default()
+        .iter()
+        .step_by(step)
+        .cloned()
+        .collect();
+    Ok(Literal::List(Rc::new(RefCell::new(sliced))))
+}
+
+/// Sorts the list in place, keeping equal items in order. Without a
+/// comparator the items must be all numbers or all strings. A comparator
+/// `fn(a, b)` returns a negative number, zero or a positive number when `a`
+/// sorts before, alongside or after `b`.
+fn sort(interpreter: &mut Interpreter, args: &[Literal]) -> Result<Literal> {
+    let items = list(&args[0])?;
+    let comparator = match args.get(1) {
+        Some(Literal::Callable(f)) if f.arity().contains(&2) => Some(f.clone()),
+        Some(_) => {
+            return Err(type_error(
+                "Comparator must be a function of two arguments.",
+            ))
+        }
+        None => None,
+    };
+    if comparator.is_none() {
+        let items = items.borrow();
+        let all_numbers = items.iter().all(|item| matches!(item, Literal::Number(_)));
+        let all_strings = items.iter().all(|item| matches!(item, Literal::String(_)));
+        if !all_numbers && !all_strings {
+            return Err(type_error(
+                "Without a comparator, sort needs all numbers or all strings.",
+            ));
+        }
+    }
+    let mut compare = |a: &Literal, b: &Literal| match &comparator {
+        Some(f) => match interpreter.call(f, &[a.clone(), b.clone()])? {
+            Literal::Number(n) => Ok(n.partial_cmp(&0.0).unwrap_or(Ordering::Equal)),
+            _ => Err(type_error("Comparator must return a number.")),
+        },
+        None => match (a, b) {
+            (Literal::Number(a), Literal::Number(b)) => a
+                .partial_cmp(b)
+                .ok_or_else(|| type_error("Cannot compare NaN.")),
+            (Literal::String(a), Literal::String(b)) => Ok(a.cmp(b)),
+            _ => unreachable!("item types were checked above"),
+        },
+    };
+    // Sorting a copy lets the comparator read the list, and leaves it as it
+    // was if the comparator fails
+    let sorted = merge_sort(items.borrow().clone(), &mut compare)?;
+    *items.borrow_mut() = sorted;
+    Ok(Literal::Nil)
+}
+
+/// A stable merge sort. Unlike `slice::sort_by`, it can't panic when a
+/// script's comparator is not a total order; it just settles on some order.
+fn merge_sort(
+    mut items: Vec<Literal>,
+    compare: &mut dyn FnMut(&Literal, &Literal) -> Result<Ordering>,
+) -> Result<Vec<Literal>> {
+    if items.len() <= 1 {
+        return Ok(items);
+    }
+    let right = items.split_off(items.len() / 2);
+    let left = merge_sort(items, compare)?;
+    let right = merge_sort(right, compare)?;
+    let mut merged = Vec::with_capacity(left.len() + right.len());
+    let mut left = left.into_iter().peekable();
+    let mut right = right.into_iter().peekable();
+    while let (Some(a), Some(b)) = (left.peek(), right.peek()) {
+        // Taking from the left on ties keeps equal items in order
+        if compare(b, a)? == Ordering::Less {
+            merged.extend(right.next());
+        } else {
+            merged.extend(left.next());
+        }
+    }
+    merged.extend(left);
+    merged.extend(right);
+    Ok(merged)
+}
+
+fn parse_int(_: &mut Interpreter, args: &[Literal]) -> Result<Literal> {
+    let s = string(&args[0])?;
+    let radix = match args.get(1) {
+        Some(Literal::Number(r)) if (2.0..=36.0).contains(r) && r.fract() == 0.0 => *r as u32,
+        Some(_) => return Err(type_error("Radix must be an integer between 2 and 36.")),
+        None => 10,
+    };
+    match i64::from_str_radix(s.trim(), radix) {
+        Ok(n) => Ok(Literal::Number(n as f64)),
+        Err(_) => Ok(Literal::Nil),
+    }
+}
+
+fn parse_float(_: &mut Interpreter, args: &[Literal]) -> Result<Literal> {
+    match string(&args[0])?.trim().parse::<f64>() {
+        Ok(n) if n.is_finite() => Ok(Literal::Number(n)),
+        _ => Ok(Literal::Nil),
+    }
+}
+
+fn number(value: &Literal) -> Result<f64> {
+    match value {
+        Literal::Number(n) => Ok(*n),
+        _ => Err(type_error("Argument must be a number.")),
+    }
+}
+
+/// Returns the magnitude of an integer-valued number.
+fn integer(value: &Literal) -> Result<u64> {
+    match value {
+        Literal::Number(n) if n.fract() == 0.0 => Ok(n.abs() as u64),
+        _ => Err(type_error("Argument must be an integer.")),
+    }
+}
+
+fn string(value: &Literal) -> Result<&str> {
+    match value {
+        Literal::String(s) => Ok(s),
+        _ => Err(type_error("Argument must be a string.")),
+    }
+}
+
+fn list(value: &Literal) -> Result<&Rc<RefCell<Vec<Literal>>>> {
+    match value {
+        Literal::List(items) => Ok(items),
+        _ => Err(type_error("Argument must be a list.")),
+    }
+}
+
+fn map(value: &Literal) -> Result<&Rc<RefCell<HashMap<MapKey, Literal>>>> {
+    match value {
+        Literal::Map(entries) => Ok(entries),
+        _ => Err(type_error("Argument must be a map.")),
+    }
+}
+
+fn type_error(message: &str) -> anyhow::Error {
+    RuntimeError::TypeError(message.to_string()).into()
+}
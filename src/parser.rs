@@ -6,24 +6,67 @@ use crate::grammar::*;
 pub struct Parser<'a> {
     tokens: &'a [Token],
     current: usize,
+    lenient: bool,
+    units: bool,
+    loop_depth: usize,
 }
 
 impl<'a> Parser<'a> {
     pub fn new(tokens: &'a [Token]) -> Self {
-        Parser { tokens, current: 0 }
+        Parser {
+            tokens,
+            current: 0,
+            lenient: false,
+            units: false,
+            loop_depth: 0,
+        }
+    }
+
+    /// In lenient mode the last statement in the source may omit its `;`.
+    pub fn set_lenient(&mut self, lenient: bool) {
+        self.lenient = lenient;
+    }
+
+    /// Parses a number directly followed by an identifier, like `5 seconds`,
+    /// as a unit conversion registered with `Interpreter::register_unit`.
+    pub fn set_units(&mut self, units: bool) {
+        self.units = units;
     }
 
     pub fn parse(&mut self) -> Result<Vec<Statement>> {
         let mut statements = vec![];
         while !self.is_at_end() {
-            statements.push(self.declaration()?);
+            if self.match_(&[TokenType::EXPORT]) {
+                statements.push(self.export()?);
+            } else {
+                statements.push(self.declaration()?);
+            }
         }
         Ok(statements)
     }
 
+    /// Parses source that must consist of exactly one expression. Any token
+    /// after it is reported with `trailing_error`, so callers can word the
+    /// error for their context.
+    pub fn parse_expression(&mut self, trailing_error: &str) -> Result<Expression> {
+        let expression = self.expression()?;
+        if !self.is_at_end() {
+            return Err(Parser::error(self.peek(), trailing_error));
+        }
+        Ok(expression)
+    }
+
     fn declaration(&mut self) -> Result<Statement> {
-        if self.match_(&[TokenType::FUN]) {
-            self.function("function")
+        if self.check(&TokenType::EXPORT) {
+            // Top-level exports are handled by `parse`
+            Err(Parser::error(
+                self.peek(),
+                "Can only export top-level declarations.",
+            ))
+        } else if self.check(&TokenType::AT) {
+            self.annotated_declaration()
+        } else if self.match_(&[TokenType::FUN]) {
+            self.function("function", vec![])
         } else if self.match_(&[TokenType::VAR]) {
             self.variable()
         } else {
@@ -31,10 +74,41 @@ impl<'a> Parser<'a> {
         }
     }
 
-    fn function(&mut self, kind: &str) -> Result<Statement> {
-        let name = self
-            .consume(&TokenType::IDENTIFIER, &format!("Expect {kind} name."))?
-            .clone();
+    fn export(&mut self) -> Result<Statement> {
+        let keyword = self.previous().clone();
+        let declaration = if self.check(&TokenType::AT) {
+            self.annotated_declaration()?
+        } else if self.match_(&[TokenType::FUN]) {
+            self.function("function", vec![])?
+        } else if self.match_(&[TokenType::VAR]) {
+            self.variable()?
+        } else {
+            return Err(Parser::error(
+                self.peek(),
+                "Expect function or variable declaration after 'export'.",
+            ));
+        };
+        Ok(Statement::Export {
+            keyword,
+            declaration: Box::new(declaration),
+        })
+    }
+
+    fn annotated_declaration(&mut self) -> Result<Statement> {
+        let mut annotations = vec![];
+        while self.match_(&[TokenType::AT]) {
+            let name = self.consume(&TokenType::IDENTIFIER, "Expect annotation name after '@'.")?;
+            annotations.push(name.lexeme.clone());
+        }
+        self.consume(
+            &TokenType::FUN,
+            "Expect function declaration after annotations.",
+        )?;
+        self.function("function", annotations)
+    }
+
+    fn function(&mut self, kind: &str, annotations: Vec<String>) -> Result<Statement> {
+        let name = self.consume_name(kind)?;
         self.consume(
             &TokenType::LEFT_PAREN,
             &format!("Expect '(' after {kind} name."),
@@ -48,10 +122,7 @@ impl<'a> Parser<'a> {
                         "Cannot have more than 255 parameters.",
                     ));
                 }
-                params.push(
-                    self.consume(&TokenType::IDENTIFIER, "Expect parameter name.")?
-                        .clone(),
-                );
+                params.push(self.consume_name("parameter")?);
                 if !self.match_(&[TokenType::COMMA]) {
                     break;
                 }
@@ -62,35 +133,71 @@ impl<'a> Parser<'a> {
             &TokenType::LEFT_BRACE,
             &format!("Expect '{{' before {kind} body."),
         )?;
-        let body = self.block()?;
-        Ok(Statement::Function { name, params, body })
+        // A loop outside the function can't be broken out of from inside it
+        let loop_depth = std::mem::take(&mut self.loop_depth);
+        let body = self.block();
+        self.loop_depth = loop_depth;
+        let body = body?;
+        Ok(Statement::Function {
+            name,
+            params,
+            body,
+            annotations,
+        })
     }
 
     fn variable(&mut self) -> Result<Statement> {
-        let name = self
-            .consume(&TokenType::IDENTIFIER, "Expect variable name.")?
-            .clone();
-        let init = if self.match_(&[TokenType::EQUAL]) {
-            Some(self.expression()?)
-        } else {
-            None
-        };
+        let mut declarations = vec![];
+        loop {
+            let name = self.consume_name("variable")?;
+            let init = if self.match_(&[TokenType::EQUAL]) {
+                Some(self.expression()?)
+            } else {
+                None
+            };
+            declarations.push(Statement::Variable { name, init });
+            if !self.match_(&[TokenType::COMMA]) {
+                break;
+            }
+        }
         self.consume(
             &TokenType::SEMICOLON,
             "Expect ';' after variable declaration.",
         )?;
-        Ok(Statement::Variable { name, init })
+        if declarations.len() == 1 {
+            Ok(declarations.remove(0))
+        } else {
+            Ok(Statement::VarGroup(declarations))
+        }
     }
 
     fn statement(&mut self) -> Result<Statement> {
-        if self.match_(&[TokenType::FOR]) {
+        if self.match_(&[TokenType::SEMICOLON]) {
+            // Empty statement
+            Ok(Statement::Block(vec![]))
+        } else if self.match_(&[TokenType::BREAK]) {
+            let keyword = self.loop_jump("break")?;
+            Ok(Statement::Break(keyword))
+        } else if self.match_(&[TokenType::CONTINUE]) {
+            let keyword = self.loop_jump("continue")?;
+            Ok(Statement::Continue(keyword))
+        } else if self.match_(&[TokenType::DEBUGGER]) {
+            let keyword = self.previous().clone();
+            self.consume(&TokenType::SEMICOLON, "Expect ';' after 'debugger'.")?;
+            Ok(Statement::Debugger(keyword))
+        } else if self.match_(&[TokenType::FOR]) {
             self.for_statement()
         } else if self.match_(&[TokenType::IF]) {
             self.if_statement()
+        } else if self.match_(&[TokenType::IMPORT]) {
+            self.import_statement()
         } else if self.match_(&[TokenType::PRINT]) {
-            let expression = self.expression()?;
-            self.consume(&TokenType::SEMICOLON, "Expect ';' after value.")?;
-            Ok(Statement::Print(expression))
+            let keyword = self.previous().clone();
+            let value = self.expression()?;
+            self.consume_terminator("Expect ';' after value.")?;
+            Ok(Statement::Print { keyword, value })
+        } else if self.match_(&[TokenType::REPEAT]) {
+            self.repeat_statement()
         } else if self.match_(&[TokenType::RETURN]) {
             self.return_statement()
         } else if self.match_(&[TokenType::WHILE]) {
@@ -98,18 +205,24 @@ impl<'a> Parser<'a> {
         } else if self.match_(&[TokenType::LEFT_BRACE]) {
             Ok(Statement::Block(self.block()?))
         } else {
-            let expression = self.expression()?;
-            self.consume(&TokenType::SEMICOLON, "Expect ';' after expression.")?;
+            let expression = self.expression_list()?;
+            self.consume_terminator("Expect ';' after expression.")?;
             Ok(Statement::Expression(expression))
         }
     }
 
     fn for_statement(&mut self) -> Result<Statement> {
+        let keyword = self.previous().clone();
         self.consume(&TokenType::LEFT_PAREN, "Expect '(' after 'for'.")?;
 
         let initializer = if self.match_(&[TokenType::SEMICOLON]) {
             None
         } else if self.match_(&[TokenType::VAR]) {
+            if self.check_next(&TokenType::IN) {
+                let name = self.consume_name("variable")?;
+                self.advance();
+                return self.for_in_statement(keyword, name);
+            }
             Some(self.variable()?)
         } else {
             Some(self.statement()?)
@@ -129,15 +242,12 @@ impl<'a> Parser<'a> {
         };
         self.consume(&TokenType::RIGHT_PAREN, "Expect ')' after for clauses.")?;
 
-        let mut body = self.statement()?;
-
-        if let Some(increment) = increment {
-            body = Statement::Block(vec![body, Statement::Expression(increment)]);
-        }
-
-        body = Statement::While {
+        // The increment is kept apart from the body so `continue` still runs it
+        let mut body = Statement::While {
+            keyword,
             condition,
-            body: Box::new(body),
+            body: Box::new(self.loop_body()?),
+            increment,
         };
 
         if let Some(initializer) = initializer {
@@ -147,39 +257,133 @@ impl<'a> Parser<'a> {
         Ok(body)
     }
 
+    fn for_in_statement(&mut self, keyword: Token, name: Token) -> Result<Statement> {
+        let iterable = self.expression()?;
+        self.consume(&TokenType::RIGHT_PAREN, "Expect ')' after for clauses.")?;
+        let body = Box::new(self.loop_body()?);
+        Ok(Statement::ForIn {
+            keyword,
+            name,
+            iterable,
+            body,
+        })
+    }
+
     fn if_statement(&mut self) -> Result<Statement> {
-        self.consume(&TokenType::LEFT_PAREN, "Expect '(' after 'if'.")?;
-        let condition = self.expression()?;
-        self.consume(&TokenType::RIGHT_PAREN, "Expect ')' after if condition.")?;
-        let then_branch = Box::new(self.statement()?);
-        let else_branch = if self.match_(&[TokenType::ELSE]) {
-            Some(Box::new(self.statement()?))
-        } else {
-            None
-        };
+        let mut branches = vec![];
+        let mut else_branch = None;
+        // `else if` continues the loop instead of recursing, so a long chain
+        // doesn't grow the stack
+        loop {
+            let keyword = self.previous().clone();
+            self.consume(&TokenType::LEFT_PAREN, "Expect '(' after 'if'.")?;
+            let condition = self.expression()?;
+            self.consume(&TokenType::RIGHT_PAREN, "Expect ')' after if condition.")?;
+            branches.push((keyword, condition, self.statement()?));
+            // Eagerly consuming `else` binds it to the nearest `if`, so in
+            // `if (a) if (b) x(); else y();` the else belongs to `if (b)`
+            if !self.match_(&[TokenType::ELSE]) {
+                break;
+            }
+            if !self.match_(&[TokenType::IF]) {
+                else_branch = Some(Box::new(self.statement()?));
+                break;
+            }
+        }
+        if branches.len() > 1 {
+            return Ok(Statement::IfChain {
+                branches,
+                else_branch,
+            });
+        }
+        let (keyword, condition, then_branch) = branches.remove(0);
         Ok(Statement::If {
+            keyword,
             condition,
-            then_branch,
+            then_branch: Box::new(then_branch),
             else_branch,
         })
     }
 
+    fn import_statement(&mut self) -> Result<Statement> {
+        let path = self
+            .consume(&TokenType::STRING, "Expect file path after 'import'.")?
+            .clone();
+        self.consume(&TokenType::SEMICOLON, "Expect ';' after import path.")?;
+        Ok(Statement::Import(path))
+    }
+
     fn return_statement(&mut self) -> Result<Statement> {
+        let keyword = self.previous().clone();
         let value = if !self.check(&TokenType::SEMICOLON) {
             Some(self.expression()?)
         } else {
             None
         };
         self.consume(&TokenType::SEMICOLON, "Expect ';' after return value.")?;
-        Ok(Statement::Return { value })
+        Ok(Statement::Return { keyword, value })
     }
 
     fn while_statement(&mut self) -> Result<Statement> {
+        let keyword = self.previous().clone();
         self.consume(&TokenType::LEFT_PAREN, "Expect '(' after 'while'.")?;
         let condition = self.expression()?;
         self.consume(&TokenType::RIGHT_PAREN, "Expect ')' after condition.")?;
-        let body = Box::new(self.statement()?);
-        Ok(Statement::While { condition, body })
+        let body = Box::new(self.loop_body()?);
+        Ok(Statement::While {
+            keyword,
+            condition,
+            body,
+            increment: None,
+        })
+    }
+
+    // `repeat (count) body` or `repeat body until (condition);`. A body
+    // that starts with `(` would read as a count, so it needs braces.
+    fn repeat_statement(&mut self) -> Result<Statement> {
+        let keyword = self.previous().clone();
+        if !self.check(&TokenType::LEFT_PAREN) {
+            let body = Box::new(self.loop_body()?);
+            self.consume(&TokenType::UNTIL, "Expect 'until' after repeat body.")?;
+            self.consume(&TokenType::LEFT_PAREN, "Expect '(' after 'until'.")?;
+            let condition = self.expression()?;
+            self.consume(&TokenType::RIGHT_PAREN, "Expect ')' after condition.")?;
+            self.consume_terminator("Expect ';' after repeat-until loop.")?;
+            return Ok(Statement::RepeatUntil {
+                keyword,
+                body,
+                condition,
+            });
+        }
+        self.consume(&TokenType::LEFT_PAREN, "Expect '(' after 'repeat'.")?;
+        let count = self.expression()?;
+        self.consume(&TokenType::RIGHT_PAREN, "Expect ')' after repeat count.")?;
+        let body = Box::new(self.loop_body()?);
+        Ok(Statement::Repeat {
+            keyword,
+            count,
+            body,
+        })
+    }
+
+    fn loop_body(&mut self) -> Result<Statement> {
+        self.loop_depth += 1;
+        let body = self.statement();
+        self.loop_depth -= 1;
+        body
+    }
+
+    fn loop_jump(&mut self, keyword: &str) -> Result<Token> {
+        let token = self.previous().clone();
+        if self.loop_depth == 0 {
+            let message = format!("Can't use '{keyword}' outside of a loop.");
+            return Err(Parser::error(&token, &message));
+        }
+        self.consume(
+            &TokenType::SEMICOLON,
+            &format!("Expect ';' after '{keyword}'."),
+        )?;
+        Ok(token)
     }
 
     fn block(&mut self) -> Result<Vec<Statement>> {
@@ -191,16 +395,65 @@ impl<'a> Parser<'a> {
         Ok(statements)
     }
 
+    // The comma operator binds more loosely than assignment, so
+    // `a = 1, b = 2` is two assignments. It is only parsed inside
+    // parentheses and in expression statements, where a comma can't be
+    // mistaken for an argument or parameter separator.
+    fn expression_list(&mut self) -> Result<Expression> {
+        let mut expressions = vec![self.expression()?];
+        while self.match_(&[TokenType::COMMA]) {
+            expressions.push(self.expression()?);
+        }
+        if expressions.len() == 1 {
+            Ok(expressions.remove(0))
+        } else {
+            Ok(Expression::Sequence(expressions))
+        }
+    }
+
     pub fn expression(&mut self) -> Result<Expression> {
         let expression = self.logic_or()?;
-        if self.match_(&[TokenType::EQUAL]) {
+        if self.match_(&[
+            TokenType::EQUAL,
+            TokenType::AND_EQUAL,
+            TokenType::OR_EQUAL,
+            TokenType::QUESTION_QUESTION_EQUAL,
+        ]) {
+            let op = self.previous().clone();
             let value = self.expression()?;
             if let Expression::Variable(name) = expression {
+                let value = match op.token_type {
+                    TokenType::EQUAL => value,
+                    // `x or= y` is `x = x or y`, so `y` short-circuits as usual.
+                    // `x ??= y` likewise only evaluates `y` when `x` is nil
+                    _ => Expression::Logical {
+                        left: Box::new(Expression::Variable(name.clone())),
+                        op,
+                        right: Box::new(value),
+                    },
+                };
                 return Ok(Expression::Assign {
                     name,
                     value: Box::new(value),
                 });
             }
+            // Compound assignment to an element isn't supported, since it
+            // would evaluate the list and index expressions twice
+            if let Expression::Index {
+                object,
+                bracket,
+                index,
+            } = expression
+            {
+                if op.token_type == TokenType::EQUAL {
+                    return Ok(Expression::IndexSet {
+                        object,
+                        bracket,
+                        index,
+                        value: Box::new(value),
+                    });
+                }
+            }
             Err(Parser::error(self.previous(), "Invalid assignment target."))
         } else {
             Ok(expression)
@@ -236,6 +489,18 @@ impl<'a> Parser<'a> {
     fn equality(&mut self) -> Result<Expression> {
         self.binary_operation(
             &[TokenType::BANG_EQUAL, TokenType::EQUAL_EQUAL],
+            Self::membership,
+        )
+    }
+
+    fn membership(&mut self) -> Result<Expression> {
+        self.binary_operation(&[TokenType::IN], Self::range)
+    }
+
+    // Ranges bind more loosely than comparison, so `0..n + 1` is `0..(n + 1)`
+    fn range(&mut self) -> Result<Expression> {
+        self.binary_operation(
+            &[TokenType::DOT_DOT, TokenType::DOT_DOT_EQUAL],
             Self::comparison,
         )
     }
@@ -295,6 +560,16 @@ impl<'a> Parser<'a> {
         loop {
             if self.match_(&[TokenType::LEFT_PAREN]) {
                 expression = self.finish_call(expression)?;
+            } else if self.match_(&[TokenType::LEFT_BRACKET]) {
+                let index = self.expression()?;
+                let bracket = self
+                    .consume(&TokenType::RIGHT_BRACKET, "Expect ']' after index.")?
+                    .clone();
+                expression = Expression::Index {
+                    object: Box::new(expression),
+                    bracket,
+                    index: Box::new(index),
+                };
             } else {
                 break;
             }
@@ -318,9 +593,12 @@ impl<'a> Parser<'a> {
                 }
             }
         }
-        self.consume(&TokenType::RIGHT_PAREN, "Expect ')' after arguments.")?;
+        let paren = self
+            .consume(&TokenType::RIGHT_PAREN, "Expect ')' after arguments.")?
+            .clone();
         Ok(Expression::Call {
             callee: Box::new(callee),
+            paren,
             arguments,
         })
     }
@@ -332,20 +610,81 @@ impl<'a> Parser<'a> {
             Expression::Literal(Literal::Boolean(true))
         } else if self.match_(&[TokenType::NIL]) {
             Expression::Literal(Literal::Nil)
+        } else if self.units
+            && self.check(&TokenType::NUMBER)
+            && self.check_next(&TokenType::IDENTIFIER)
+        {
+            let Some(Literal::Number(value)) = self.advance().literal else {
+                unreachable!()
+            };
+            let unit = self.advance().clone();
+            Expression::Unit { value, unit }
         } else if self.match_(&[TokenType::NUMBER, TokenType::STRING]) {
             Expression::Literal(self.previous().literal.clone().unwrap())
         } else if self.match_(&[TokenType::IDENTIFIER]) {
             Expression::Variable(self.previous().clone())
         } else if self.match_(&[TokenType::LEFT_PAREN]) {
-            let expr = self.expression()?;
+            let expr = self.expression_list()?;
             self.consume(&TokenType::RIGHT_PAREN, "Expect ')' after expression.")?;
             Expression::Grouping(Box::new(expr))
+        } else if self.match_(&[TokenType::IF]) {
+            self.conditional()?
+        } else if self.match_(&[TokenType::LEFT_BRACKET]) {
+            let mut items = vec![];
+            if !self.check(&TokenType::RIGHT_BRACKET) {
+                loop {
+                    items.push(self.expression()?);
+                    if !self.match_(&[TokenType::COMMA]) {
+                        break;
+                    }
+                }
+            }
+            self.consume(&TokenType::RIGHT_BRACKET, "Expect ']' after list items.")?;
+            Expression::ListLiteral(items)
+        } else if self.match_(&[TokenType::LEFT_BRACE]) {
+            // Blocks are statements, so a brace in an expression is a map
+            let mut entries = vec![];
+            if !self.check(&TokenType::RIGHT_BRACE) {
+                loop {
+                    let key = self.expression()?;
+                    self.consume(&TokenType::COLON, "Expect ':' after map key.")?;
+                    entries.push((key, self.expression()?));
+                    if !self.match_(&[TokenType::COMMA]) {
+                        break;
+                    }
+                }
+            }
+            self.consume(&TokenType::RIGHT_BRACE, "Expect '}' after map entries.")?;
+            Expression::MapLiteral(entries)
+        } else if self.check(&TokenType::THIS) && self.check_next(&TokenType::EQUAL) {
+            return Err(Parser::error(self.peek(), "Cannot assign to 'this'."));
         } else {
             return Err(Parser::error(self.peek(), "Expect expression."));
         };
         Ok(expr)
     }
 
+    // `if` only starts a conditional expression where an expression is
+    // expected; at the start of a statement it is always an if statement.
+    // The `else` is optional and a missing one yields nil, the way an if
+    // statement without `else` does nothing. A dangling `else` binds to the
+    // nearest `then`.
+    fn conditional(&mut self) -> Result<Expression> {
+        let condition = self.expression()?;
+        self.consume(&TokenType::THEN, "Expect 'then' after condition.")?;
+        let then_branch = self.expression()?;
+        let else_branch = if self.match_(&[TokenType::ELSE]) {
+            Some(Box::new(self.expression()?))
+        } else {
+            None
+        };
+        Ok(Expression::Conditional {
+            condition: Box::new(condition),
+            then_branch: Box::new(then_branch),
+            else_branch,
+        })
+    }
+
     fn match_(&mut self, token_types: &[TokenType]) -> bool {
         token_types.iter().any(|token_type| {
             if self.check(token_type) {
@@ -364,10 +703,38 @@ impl<'a> Parser<'a> {
         Err(Parser::error(self.peek(), message))
     }
 
+    /// Consumes the identifier naming a declared `kind`, such as a variable,
+    /// with a clearer error when a reserved keyword is used instead.
+    fn consume_name(&mut self, kind: &str) -> Result<Token> {
+        let token = self.peek();
+        if token.token_type != TokenType::IDENTIFIER
+            && TokenType::get_token_type(&token.lexeme) == token.token_type
+        {
+            let message = format!(
+                "'{}' is a reserved keyword and cannot be used as a {kind} name.",
+                token.lexeme
+            );
+            return Err(Parser::error(token, &message));
+        }
+        let message = format!("Expect {kind} name.");
+        Ok(self.consume(&TokenType::IDENTIFIER, &message)?.clone())
+    }
+
+    fn consume_terminator(&mut self, message: &str) -> Result<()> {
+        if !(self.lenient && self.is_at_end()) {
+            self.consume(&TokenType::SEMICOLON, message)?;
+        }
+        Ok(())
+    }
+
     fn check(&self, token_type: &TokenType) -> bool {
         !self.is_at_end() && self.peek().token_type == *token_type
     }
 
+    fn check_next(&self, token_type: &TokenType) -> bool {
+        !self.is_at_end() && self.tokens[self.current + 1].token_type == *token_type
+    }
+
     fn advance(&mut self) -> &Token {
         if !self.is_at_end() {
             self.current += 1;
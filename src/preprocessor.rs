@@ -0,0 +1,69 @@
+use anyhow::Result;
+use std::collections::HashSet;
+
+use crate::error::RuntimeError;
+
+/// Drops the lines between `@if FEATURE` and `@endif` unless `FEATURE` is
+/// defined. Directives and dropped lines are left blank rather than
+/// removed, so line numbers in later errors still match the source.
+/// Blocks cannot be nested, and lines inside a string literal are never
+/// directives.
+pub fn preprocess(source: &str, features: &HashSet<String>) -> Result<String> {
+    let mut lines = vec![];
+    // The line of the open `@if`, and whether its feature is defined
+    let mut open_block: Option<(usize, bool)> = None;
+    let mut in_string = false;
+    for (index, line) in source.split('\n').enumerate() {
+        let line_number = index + 1;
+        let starts_in_string = in_string;
+        in_string = ends_in_string(line, in_string);
+        let mut words = line.split_whitespace();
+        match words.next().filter(|_| !starts_in_string) {
+            Some("@if") => {
+                let (Some(feature), None) = (words.next(), words.next()) else {
+                    return Err(error(line_number, "Expect one feature name after '@if'."));
+                };
+                if open_block.is_some() {
+                    return Err(error(line_number, "Nested '@if' is not supported."));
+                }
+                open_block = Some((line_number, features.contains(feature)));
+                lines.push("");
+            }
+            Some("@endif") if words.next().is_none() => {
+                if open_block.take().is_none() {
+                    return Err(error(line_number, "'@endif' without a matching '@if'."));
+                }
+                lines.push("");
+            }
+            _ if matches!(open_block, Some((_, false))) => lines.push(""),
+            _ => lines.push(line),
+        }
+    }
+    if let Some((line_number, _)) = open_block {
+        return Err(error(line_number, "Unterminated '@if'."));
+    }
+    Ok(lines.join("\n"))
+}
+
+/// Whether a string literal is open at the end of `line`, given whether one
+/// was open at its start. Strings have no escapes, so only quotes and `//`
+/// comments matter.
+fn ends_in_string(line: &str, mut in_string: bool) -> bool {
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => in_string = !in_string,
+            '/' if !in_string && chars.peek() == Some(&'/') => break,
+            _ => (),
+        }
+    }
+    in_string
+}
+
+fn error(line: usize, message: &str) -> anyhow::Error {
+    RuntimeError::PreprocessorError {
+        line,
+        message: message.to_string(),
+    }
+    .into()
+}
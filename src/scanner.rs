@@ -6,6 +6,13 @@ pub struct Scanner {
     start: usize,
     current: usize,
     line: usize,
+    quiet: bool,
+    escapes: bool,
+    /// The line input ended on while inside a string, which `feed` may
+    /// still close.
+    open_string: Option<usize>,
+    /// Whether `error` was set before the open string was reported.
+    error_before_open_string: bool,
     pub error: bool,
 }
 
@@ -17,21 +24,75 @@ impl Scanner {
             start: 0,
             current: 0,
             line: 1,
+            quiet: false,
+            escapes: false,
+            open_string: None,
+            error_before_open_string: false,
             error: false,
         }
     }
 
+    /// Creates a scanner for source that continues an earlier chunk, so
+    /// line numbers start at `start_line` instead of 1.
+    pub fn new_at(input: &str, start_line: usize) -> Self {
+        Scanner {
+            line: start_line,
+            ..Scanner::new(input)
+        }
+    }
+
+    /// Appends more source. The next `scan_tokens` call resumes where the
+    /// previous one stopped instead of rescanning from the beginning. A
+    /// string left open by the earlier chunk is rescanned with the new input.
+    pub fn feed(&mut self, input: &str) {
+        if self.open_string.take().is_some() {
+            self.error = self.error_before_open_string;
+        }
+        if self
+            .tokens
+            .last()
+            .is_some_and(|token| token.token_type == TokenType::EOF)
+        {
+            self.tokens.pop();
+        }
+        self.source.extend(input.chars());
+    }
+
+    /// Stops errors from being printed to stderr. `error` is still set.
+    pub fn set_quiet(&mut self, quiet: bool) {
+        self.quiet = quiet;
+    }
+
+    /// Decodes `\n`, `\r`, `\t`, `\"` and `\\` in plain strings. Off by
+    /// default, as Lox strings have no escapes. Raw strings are always
+    /// verbatim.
+    pub fn set_escapes(&mut self, escapes: bool) {
+        self.escapes = escapes;
+    }
+
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
     pub fn scan_tokens(&mut self) -> Vec<Token> {
-        while !self.is_at_end() {
+        while !self.is_at_end() && self.open_string.is_none() {
             self.start = self.current;
             self.scan_token();
         }
+        let line = self.line;
+        // Only reported once the input is known to end inside the string
+        if let Some(end_line) = self.open_string {
+            self.line = end_line;
+            self.report("Unterminated string.");
+        }
         self.tokens.push(Token {
             token_type: TokenType::EOF,
             lexeme: String::new(),
             literal: None,
             line: self.line,
         });
+        // Resume counting from the start of an open string
+        self.line = line;
         self.tokens.clone()
     }
 
@@ -42,26 +103,57 @@ impl Scanner {
             ')' => self.add_token(TokenType::RIGHT_PAREN),
             '{' => self.add_token(TokenType::LEFT_BRACE),
             '}' => self.add_token(TokenType::RIGHT_BRACE),
+            '[' => self.add_token(TokenType::LEFT_BRACKET),
+            ']' => self.add_token(TokenType::RIGHT_BRACKET),
+            // Only an annotation like `@name` is a token; a lone '@' stays an error
+            '@' if self.peek().is_alphabetic() || self.peek() == '_' => {
+                self.add_token(TokenType::AT)
+            }
+            ':' => self.add_token(TokenType::COLON),
             ',' => self.add_token(TokenType::COMMA),
+            '.' if self.match_('.') => {
+                if self.match_('=') {
+                    self.add_token(TokenType::DOT_DOT_EQUAL)
+                } else {
+                    self.add_token(TokenType::DOT_DOT)
+                }
+            }
             '.' => self.add_token(TokenType::DOT),
             '-' => self.add_token(TokenType::MINUS),
             '+' => self.add_token(TokenType::PLUS),
             ';' => self.add_token(TokenType::SEMICOLON),
             '*' => self.add_token(TokenType::STAR),
             '=' | '!' | '<' | '>' => self.handle_comparison(c),
+            '?' if self.peek() == '?' && self.peek_next() == '=' => {
+                self.advance();
+                self.advance();
+                self.add_token(TokenType::QUESTION_QUESTION_EQUAL)
+            }
             '/' => self.handle_slash(),
-            ' ' | '\r' | '\t' => (),
+            ' ' | '\t' => (),
+            // A bare '\r' is an old Mac line ending; in "\r\n" the '\n' counts
+            '\r' if self.peek() != '\n' => self.line += 1,
+            '\r' => (),
             '\n' => self.line += 1,
-            '"' => self.handle_string(),
+            '"' => self.handle_string(false),
+            'r' if self.peek() == '"' => {
+                // Raw string: the contents are taken verbatim, backslashes included
+                self.advance();
+                self.handle_string(true)
+            }
             c if c.is_ascii_digit() => self.handle_number(),
             c if c.is_alphabetic() || c == '_' => self.handle_identifier(),
-            _ => {
-                eprintln!("[line {}] Error: Unexpected character: {}", self.line, c);
-                self.error = true;
-            }
+            _ => self.report(&format!("Unexpected character: {c}")),
         };
     }
 
+    fn report(&mut self, message: &str) {
+        if !self.quiet {
+            eprintln!("[line {}] Error: {message}", self.line);
+        }
+        self.error = true;
+    }
+
     fn add_token(&mut self, token_type: TokenType) {
         self.add_token_with_literal(token_type, None);
     }
@@ -99,30 +191,46 @@ impl Scanner {
     }
 
     fn advance_end_of_line(&mut self) {
-        while self.peek() != '\n' && !self.is_at_end() {
+        while !matches!(self.peek(), '\n' | '\r') && !self.is_at_end() {
             self.advance();
         }
     }
 
-    fn handle_string(&mut self) {
+    fn handle_string(&mut self, raw: bool) {
+        let start_line = self.line;
+        let escapes = self.escapes && !raw;
         while self.peek() != '"' && !self.is_at_end() {
-            if self.peek() == '\n' {
+            // Skip the backslash, so an escaped quote doesn't end the string
+            if escapes && self.peek() == '\\' {
+                self.advance();
+                if self.is_at_end() {
+                    break;
+                }
+            }
+            if self.peek() == '\n' || (self.peek() == '\r' && self.peek_next() != '\n') {
                 self.line += 1;
             }
             self.advance();
         }
 
         if self.is_at_end() {
-            eprintln!("[line {}] Error: Unterminated string.", self.line);
-            self.error = true;
+            // Rewind so that more input from `feed` rescans the whole string
+            self.open_string = Some(self.line);
+            self.error_before_open_string = self.error;
+            self.line = start_line;
+            self.current = self.start;
             return;
         }
 
         // The closing "
         self.advance();
 
-        // Trim the surrounding quotes
-        let literal = self.substr(self.start + 1, self.current - 1);
+        // Trim the prefix and surrounding quotes
+        let prefix_len = if raw { 2 } else { 1 };
+        let mut literal = self.substr(self.start + prefix_len, self.current - 1);
+        if escapes {
+            literal = unescape(&literal);
+        }
         self.add_token_with_literal(TokenType::STRING, Some(Literal::String(literal)))
     }
 
@@ -150,7 +258,18 @@ impl Scanner {
             self.advance();
         }
         let text = self.substr(self.start, self.current);
-        let token_type = TokenType::get_token_type(&text);
+        let token_type = match TokenType::get_token_type(&text) {
+            // Logical assignment: `and=` and `or=`, but not `and ==`
+            TokenType::AND if self.peek() == '=' && self.peek_next() != '=' => {
+                self.advance();
+                TokenType::AND_EQUAL
+            }
+            TokenType::OR if self.peek() == '=' && self.peek_next() != '=' => {
+                self.advance();
+                TokenType::OR_EQUAL
+            }
+            token_type => token_type,
+        };
         self.add_token(token_type)
     }
 
@@ -195,3 +314,27 @@ impl Scanner {
         self.source[self.current - 1]
     }
 }
+
+/// Decodes the escapes `set_escapes` enables. Any other backslash is kept.
+fn unescape(s: &str) -> String {
+    let mut decoded = String::new();
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            decoded.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => decoded.push('\n'),
+            Some('r') => decoded.push('\r'),
+            Some('t') => decoded.push('\t'),
+            Some(c @ ('"' | '\\')) => decoded.push(c),
+            Some(c) => {
+                decoded.push('\\');
+                decoded.push(c);
+            }
+            None => decoded.push('\\'),
+        }
+    }
+    decoded
+}
@@ -0,0 +1,22 @@
+use interpreter_starter_rust::interpret_to_string;
+
+fn run(source: &str) -> String {
+    interpret_to_string(source).unwrap()
+}
+
+#[test]
+fn annotations_are_listed_in_order() {
+    let source = "@deprecated @pure fun f() { return 1; } print annotations(f); print f();";
+    assert_eq!(run(source), "[deprecated, pure]\n1\n");
+}
+
+#[test]
+fn unannotated_function_has_empty_list() {
+    assert_eq!(run("fun f() {} print annotations(f);"), "[]\n");
+}
+
+#[test]
+fn non_function_has_no_annotations() {
+    let error = interpret_to_string("annotations(1);").unwrap_err();
+    assert_eq!(error.to_string(), "Only functions have annotations.");
+}
@@ -0,0 +1,63 @@
+use interpreter_starter_rust::interpret_to_string;
+
+fn run(source: &str) -> String {
+    interpret_to_string(source).unwrap()
+}
+
+/// Defines `side(v)`, which prints `side` before returning `v`, so a test
+/// can see whether a right-hand side was evaluated.
+const SIDE: &str = "fun side(v) { print \"side\"; return v; }\n";
+
+#[test]
+fn or_assign_only_evaluates_when_falsy() {
+    let source = format!(
+        "{SIDE}
+        var a = 1;
+        a or= side(2);
+        print a;
+        var b = false;
+        b or= side(3);
+        print b;
+        var c = nil;
+        c or= side(0);
+        print c;"
+    );
+    assert_eq!(run(&source), "1\nside\n3\nside\n0\n");
+}
+
+#[test]
+fn and_assign_only_evaluates_when_truthy() {
+    let source = format!(
+        "{SIDE}
+        var a = nil;
+        a and= side(2);
+        print a;
+        var b = 1;
+        b and= side(false);
+        print b;
+        var c = \"x\";
+        c and= side(\"y\");
+        print c;"
+    );
+    assert_eq!(run(&source), "nil\nside\nfalse\nside\ny\n");
+}
+
+#[test]
+fn nil_coalescing_assign_only_evaluates_when_nil() {
+    let source = format!(
+        "{SIDE}
+        var a = false;
+        a ??= side(1);
+        print a;
+        var b;
+        b ??= side(2);
+        print b;"
+    );
+    assert_eq!(run(&source), "false\nside\n2\n");
+}
+
+#[test]
+fn logical_assignment_is_an_expression() {
+    let source = "var a; print a ??= 5; var b = 0; print b and= 7; print b;";
+    assert_eq!(run(source), "5\n7\n7\n");
+}
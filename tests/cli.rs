@@ -0,0 +1,244 @@
+use std::{
+    io::Write,
+    process::{Command, Output, Stdio},
+};
+
+/// Runs the binary with `args`, feeding `source` through stdin as `-`.
+fn run_cli(args: &[&str], source: &str) -> Output {
+    run_script(args, source, &[])
+}
+
+/// Like `run_cli`, passing `script_args` after the `-` filename.
+fn run_script(args: &[&str], source: &str, script_args: &[&str]) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_interpreter-starter-rust"))
+        .args(args)
+        .arg("-")
+        .args(script_args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(source.as_bytes())
+        .unwrap();
+    child.wait_with_output().unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+fn stderr(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stderr).into_owned()
+}
+
+#[test]
+fn failed_assertion_exits_with_71() {
+    let output = run_cli(&["run"], "print 1;\nassert(1 == 2);\n");
+    assert_eq!(stdout(&output), "1\n");
+    assert_eq!(stderr(&output), "[line 2] Assertion failed.\n");
+    assert_eq!(output.status.code(), Some(71));
+}
+
+#[test]
+fn other_runtime_errors_exit_with_70() {
+    let output = run_cli(&["run"], "print -\"a\";\n");
+    assert_eq!(output.status.code(), Some(70));
+}
+
+#[test]
+fn lenient_mode_accepts_missing_semicolon_at_eof() {
+    let output = run_cli(&["run", "--lenient"], "print 1");
+    assert_eq!(stdout(&output), "1\n");
+    assert!(output.status.success());
+}
+
+#[test]
+fn strict_mode_rejects_missing_semicolon_at_eof() {
+    let output = run_cli(&["run"], "print 1");
+    assert_eq!(
+        stderr(&output),
+        "[line 1] Error at '': Expect ';' after value.\n"
+    );
+    assert_eq!(output.status.code(), Some(65));
+}
+
+#[test]
+fn lenient_mode_still_requires_semicolons_before_eof() {
+    let output = run_cli(&["run", "--lenient"], "print 1 print 2");
+    assert_eq!(output.status.code(), Some(65));
+}
+
+#[test]
+fn evaluate_prints_values_like_run() {
+    for (expression, expected) in [
+        ("true", "true\n"),
+        ("nil", "nil\n"),
+        ("\"str\"", "str\n"),
+        ("3 < 4", "true\n"),
+        ("10 / 4", "2.5\n"),
+    ] {
+        let evaluated = run_cli(&["evaluate"], expression);
+        assert_eq!(stdout(&evaluated), expected, "evaluate {expression}");
+        let printed = run_cli(&["run"], &format!("print {expression};"));
+        assert_eq!(stdout(&printed), expected, "print {expression}");
+    }
+}
+
+#[test]
+fn define_enables_guarded_code_in_run() {
+    let source = "@if FAST\nprint \"fast\";\n@endif\nprint \"end\";\n";
+    assert_eq!(stdout(&run_cli(&["run"], source)), "end\n");
+    let output = run_cli(&["run", "--define", "FAST"], source);
+    assert_eq!(stdout(&output), "fast\nend\n");
+}
+
+#[test]
+fn tokenize_ignores_directives() {
+    let output = run_cli(&["tokenize"], "@if X\n");
+    assert_eq!(
+        stdout(&output),
+        "AT @ null\nIF if null\nIDENTIFIER X null\nEOF  null\n"
+    );
+}
+
+#[test]
+fn lone_at_sign_is_an_unexpected_character() {
+    let output = run_cli(&["tokenize"], "@ 1");
+    assert_eq!(stderr(&output), "[line 1] Error: Unexpected character: @\n");
+    assert_eq!(stdout(&output), "NUMBER 1 1.0\nEOF  null\n");
+    assert_eq!(output.status.code(), Some(65));
+}
+
+#[test]
+fn evaluate_rejects_trailing_tokens() {
+    let output = run_cli(&["evaluate"], "1 + 2; 3");
+    assert_eq!(
+        stderr(&output),
+        "[line 1] Error at ';': evaluate expects a single expression, found trailing tokens.\n"
+    );
+    assert_eq!(output.status.code(), Some(65));
+}
+
+#[test]
+fn time_reports_phases_without_changing_output() {
+    let source = "print 1 + 2;";
+    let output = run_cli(&["run", "--time"], source);
+    assert_eq!(stdout(&output), stdout(&run_cli(&["run"], source)));
+    let phases: Vec<String> = stderr(&output)
+        .lines()
+        .map(|line| line.split(':').next().unwrap().to_string())
+        .collect();
+    assert_eq!(phases, ["preprocess", "scan", "parse", "interpret"]);
+}
+
+#[test]
+fn time_reports_phases_when_the_script_fails() {
+    let output = run_cli(&["run", "--time"], "print -\"a\";");
+    assert_eq!(output.status.code(), Some(70));
+    let stderr = stderr(&output);
+    assert!(stderr.contains("interpret: "), "{stderr}");
+    assert!(stderr.ends_with("Operand must be a number.\n"), "{stderr}");
+}
+
+#[test]
+fn time_reports_phases_before_a_parse_error() {
+    let output = run_cli(&["run", "--time"], "print ;");
+    assert_eq!(output.status.code(), Some(65));
+    let stderr = stderr(&output);
+    assert!(stderr.contains("parse: "), "{stderr}");
+    assert!(!stderr.contains("interpret: "), "{stderr}");
+}
+
+#[test]
+fn main_flag_calls_main_with_script_args() {
+    let source = "fun main(args) { print len(args); print args[0]; print args[1]; }";
+    let output = run_script(&["run", "--main"], source, &["one", "--two"]);
+    assert_eq!(stdout(&output), "2\none\n--two\n");
+    assert!(output.status.success());
+}
+
+#[test]
+fn main_flag_calls_main_without_parameters() {
+    let output = run_script(&["run", "--main"], "fun main() { print \"hi\"; }", &["x"]);
+    assert_eq!(stdout(&output), "hi\n");
+    assert!(output.status.success());
+}
+
+#[test]
+fn main_flag_does_nothing_without_main() {
+    let output = run_cli(&["run", "--main"], "print \"top\";");
+    assert_eq!(stdout(&output), "top\n");
+    assert!(output.status.success());
+}
+
+#[test]
+fn main_flag_rejects_main_with_two_parameters() {
+    let output = run_cli(&["run", "--main"], "fun main(a, b) {}");
+    assert_eq!(
+        stderr(&output),
+        "'main' must take no parameters or one list of arguments.\n"
+    );
+    assert_eq!(output.status.code(), Some(70));
+}
+
+#[test]
+fn multi_line_assert_reports_the_line_it_starts_on() {
+    let source = "print 1;\nassert(\n  1 ==\n  2\n);\n";
+    let output = run_cli(&["run"], source);
+    assert_eq!(stderr(&output), "[line 2] Assertion failed.\n");
+    assert_eq!(output.status.code(), Some(71));
+}
+
+#[test]
+fn coverage_counts_loop_iterations_and_skipped_branches() {
+    let source = "var n = 0;\nfor (var i = 0; i < 3; i = i + 1) {\n  n = n + 1;\n}\nif (n > 5) {\n  print \"big\";\n}\n";
+    let output = run_cli(&["run", "--coverage"], source);
+    assert_eq!(stdout(&output), "");
+    let report = stderr(&output);
+    assert!(
+        report.starts_with("Coverage: 4 of 5 lines run\n"),
+        "{report}"
+    );
+    assert!(report.contains("line 3: 3\n"), "{report}");
+    assert!(report.contains("line 6: 0\n"), "{report}");
+}
+
+#[test]
+fn coverage_is_off_by_default() {
+    let output = run_cli(&["run"], "print 1;\n");
+    assert_eq!(stderr(&output), "");
+}
+
+#[test]
+fn dump_dot_renders_a_binary_expression() {
+    let output = run_cli(&["dump-dot"], "1 + 2;\n");
+    let dot = stdout(&output);
+    assert!(dot.starts_with("digraph AST {\n"), "{dot}");
+    assert!(dot.ends_with("}\n"), "{dot}");
+    for line in [
+        "n2 [label=\"+\"];",
+        "n3 [label=\"1.0\"];",
+        "n4 [label=\"2.0\"];",
+        "n2 -> n3;",
+        "n2 -> n4;",
+    ] {
+        assert!(dot.contains(line), "missing {line:?} in {dot}");
+    }
+    assert!(output.status.success());
+}
+
+#[test]
+fn print_last_shows_the_final_expression_value() {
+    let source = "var a = 2;\na + 3;\n";
+    let output = run_cli(&["run", "--print-last"], source);
+    assert_eq!(stdout(&output), "5\n");
+    let output = run_cli(&["run"], source);
+    assert_eq!(stdout(&output), "");
+    let output = run_cli(&["run", "--print-last"], "1 + 1;\nprint \"done\";\n");
+    assert_eq!(stdout(&output), "done\n");
+}
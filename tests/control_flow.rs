@@ -0,0 +1,166 @@
+use interpreter_starter_rust::interpret_to_string;
+
+fn run(source: &str) -> String {
+    interpret_to_string(source).unwrap()
+}
+
+fn else_if_chain(branches: usize, value: usize) -> String {
+    let mut source = format!("var x = {value};\nif (x == 0) print 0;\n");
+    for i in 1..branches {
+        source += &format!("else if (x == {i}) print {i};\n");
+    }
+    source + "else print \"none\";\n"
+}
+
+#[test]
+fn long_else_if_chain_runs_without_overflowing() {
+    assert_eq!(run(&else_if_chain(10_000, 9_999)), "9999\n");
+    assert_eq!(run(&else_if_chain(10_000, 10_000)), "none\n");
+}
+
+#[test]
+fn else_if_chain_takes_first_true_branch() {
+    let source =
+        "if (false) print 1; else if (true) print 2; else if (true) print 3; else print 4;";
+    assert_eq!(run(source), "2\n");
+}
+
+#[test]
+fn continue_in_for_loop_still_runs_increment() {
+    let source = "for (var i = 0; i < 5; i = i + 1) { if (i == 2) continue; print i; }";
+    assert_eq!(run(source), "0\n1\n3\n4\n");
+}
+
+#[test]
+fn continue_in_for_loop_without_body_block() {
+    let source = "for (var i = 0; i < 3; i = i + 1) continue; print \"done\";";
+    assert_eq!(run(source), "done\n");
+}
+
+#[test]
+fn dangling_else_binds_to_nearest_if() {
+    // The else belongs to `if (b)`, so nothing prints when `a` is false
+    let source = "var a = false; var b = false; if (a) if (b) print \"x\"; else print \"y\";";
+    assert_eq!(run(source), "");
+    let source = "var a = true; var b = false; if (a) if (b) print \"x\"; else print \"y\";";
+    assert_eq!(run(source), "y\n");
+}
+
+#[test]
+fn conditional_expression_picks_a_branch() {
+    let source = "
+        print if 1 < 2 then \"yes\" else \"no\";
+        var x = if nil then 1 else 2;
+        print x;
+    ";
+    assert_eq!(run(source), "yes\n2\n");
+}
+
+#[test]
+fn conditional_expression_without_else_is_nil() {
+    assert_eq!(run("print if false then 1;"), "nil\n");
+    assert_eq!(run("print if true then 1;"), "1\n");
+}
+
+#[test]
+fn conditional_expressions_nest() {
+    let source = "
+        fun grade(n) {
+            return if n > 90 then \"a\" else if n > 80 then \"b\" else \"c\";
+        }
+        print grade(95);
+        print grade(85);
+        print grade(10);
+        print if true then if false then 1 else 2 else 3;
+        print if false then if true then 1 else 2;
+    ";
+    assert_eq!(run(source), "a\nb\nc\n2\nnil\n");
+}
+
+#[test]
+fn conditional_expression_only_evaluates_one_branch() {
+    let source = "
+        fun side(v) { print v; return v; }
+        print if true then side(\"then\") else side(\"else\");
+    ";
+    assert_eq!(run(source), "then\nthen\n");
+}
+
+#[test]
+fn comma_operator_runs_left_to_right_and_yields_the_last_value() {
+    let source = "
+        fun side(v) { print v; return v; }
+        var x = (side(1), side(2), side(3));
+        print x;
+        side(\"a\"), side(\"b\");
+    ";
+    assert_eq!(run(source), "1\n2\n3\n3\na\nb\n");
+}
+
+#[test]
+fn commas_in_calls_and_lists_still_separate_items() {
+    let source = "
+        fun add(a, b) { return a + b; }
+        print add(1, 2);
+        print add((1, 2), 3);
+        print [(1, 2), 3];
+    ";
+    assert_eq!(run(source), "3\n5\n[2, 3]\n");
+}
+
+#[test]
+fn repeat_runs_the_body_a_fixed_number_of_times() {
+    assert_eq!(run("repeat(3) print \"x\";"), "x\nx\nx\n");
+    assert_eq!(run("repeat(0) print \"x\"; print \"done\";"), "done\n");
+}
+
+#[test]
+fn repeat_count_supports_break_and_continue() {
+    let source = "
+        var i = 0;
+        repeat(10) {
+            i = i + 1;
+            if (i == 2) continue;
+            if (i == 4) break;
+            print i;
+        }
+        print i;
+    ";
+    assert_eq!(run(source), "1\n3\n4\n");
+}
+
+#[test]
+fn repeat_until_runs_the_body_at_least_once() {
+    let source = "
+        var i = 10;
+        repeat {
+            print i;
+            i = i + 1;
+        } until (i > 5);
+        var j = 0;
+        repeat j = j + 1; until (j == 3);
+        print j;
+    ";
+    assert_eq!(run(source), "10\n3\n");
+}
+
+#[test]
+fn repeat_until_supports_break_and_continue() {
+    let source = "
+        var i = 0;
+        repeat {
+            i = i + 1;
+            if (i == 2) continue;
+            if (i == 4) break;
+            print i;
+        } until (i >= 10);
+        print i;
+        var k = 0;
+        repeat {
+            k = k + 1;
+            continue;
+        } until (k == 3);
+        print k;
+    ";
+    assert_eq!(run(source), "1\n3\n4\n3\n");
+}
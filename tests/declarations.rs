@@ -0,0 +1,61 @@
+use interpreter_starter_rust::interpret_to_string;
+
+fn run(source: &str) -> String {
+    interpret_to_string(source).unwrap()
+}
+
+fn run_err(source: &str) -> String {
+    interpret_to_string(source).unwrap_err().to_string()
+}
+
+#[test]
+fn keyword_as_a_name_is_explained() {
+    assert_eq!(
+        run_err("var class = 1;"),
+        "[line 1] Error at 'class': 'class' is a reserved keyword and cannot be used as a variable name."
+    );
+    assert_eq!(
+        run_err("var x = 1, if = 2;"),
+        "[line 1] Error at 'if': 'if' is a reserved keyword and cannot be used as a variable name."
+    );
+    assert_eq!(
+        run_err("fun return() {}"),
+        "[line 1] Error at 'return': 'return' is a reserved keyword and cannot be used as a function name."
+    );
+    assert_eq!(
+        run_err("fun f(fun) {}"),
+        "[line 1] Error at 'fun': 'fun' is a reserved keyword and cannot be used as a parameter name."
+    );
+}
+
+#[test]
+fn several_variables_in_one_declaration() {
+    assert_eq!(
+        run("var a = 1, b = a + 1, c; print a; print b; print c;"),
+        "1\n2\nnil\n"
+    );
+}
+
+#[test]
+fn each_declared_variable_needs_a_name() {
+    assert_eq!(
+        run_err("var a, b = 1, 2;"),
+        "[line 1] Error at '2': Expect variable name."
+    );
+    assert_eq!(
+        run_err("var a = 1, = 2;"),
+        "[line 1] Error at '=': Expect variable name."
+    );
+    assert_eq!(
+        run_err("var a = 1,;"),
+        "[line 1] Error at ';': Expect variable name."
+    );
+}
+
+#[test]
+fn repeat_needs_until_or_a_count() {
+    assert_eq!(
+        run_err("repeat print 1;"),
+        "[line 1] Error at '': Expect 'until' after repeat body."
+    );
+}
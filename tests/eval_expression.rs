@@ -0,0 +1,64 @@
+use interpreter_starter_rust::{Interpreter, Literal, Parser, Scanner};
+
+#[test]
+fn evaluates_arithmetic() {
+    let mut interpreter = Interpreter::new();
+    assert_eq!(
+        interpreter.eval_expression("1 + 2").unwrap(),
+        Literal::Number(3.0)
+    );
+}
+
+#[test]
+fn sees_globals_from_earlier_runs() {
+    let mut interpreter = Interpreter::new();
+    let tokens = Scanner::new("var answer = 41;").scan_tokens();
+    let statements = Parser::new(&tokens).parse().unwrap();
+    interpreter.interpret(&statements).unwrap();
+    assert_eq!(
+        interpreter.eval_expression("answer + 1").unwrap(),
+        Literal::Number(42.0)
+    );
+}
+
+#[test]
+fn trailing_tokens_are_an_error() {
+    let error = Interpreter::new().eval_expression("1; 2").unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "[line 1] Error at ';': Expect end of expression."
+    );
+}
+
+fn interpret(interpreter: &mut Interpreter, source: &str) -> anyhow::Result<Literal> {
+    let tokens = Scanner::new(source).scan_tokens();
+    let statements = Parser::new(&tokens).parse().unwrap();
+    interpreter.interpret(&statements)
+}
+
+#[test]
+fn error_inside_a_block_restores_the_outer_scope() {
+    let mut interpreter = Interpreter::new();
+    let source = "var x = \"outer\"; { var x = \"inner\"; var y = 1; { missing; } }";
+    assert!(interpret(&mut interpreter, source).is_err());
+    assert_eq!(
+        interpreter.eval_expression("x").unwrap(),
+        Literal::String("outer".to_string())
+    );
+    assert_eq!(
+        interpreter.eval_expression("y").unwrap_err().to_string(),
+        "Undefined variable 'y'.\n[line 1]"
+    );
+}
+
+#[test]
+fn error_inside_a_function_restores_the_outer_scope() {
+    let mut interpreter = Interpreter::new();
+    let source = "var x = 1; fun f(x) { var local = x; return missing; } f(2);";
+    assert!(interpret(&mut interpreter, source).is_err());
+    assert_eq!(
+        interpreter.eval_expression("x").unwrap(),
+        Literal::Number(1.0)
+    );
+    assert!(interpreter.eval_expression("local").is_err());
+}
@@ -0,0 +1,90 @@
+use interpreter_starter_rust::interpret_to_string;
+
+fn run(source: &str) -> String {
+    interpret_to_string(source).unwrap()
+}
+
+#[test]
+fn mutually_recursive_functions() {
+    let source = "
+        fun isEven(n) { if (n == 0) return true; return isOdd(n - 1); }
+        fun isOdd(n) { if (n == 0) return false; return isEven(n - 1); }
+        print isEven(10);
+        print isOdd(7);
+    ";
+    assert_eq!(run(source), "true\ntrue\n");
+}
+
+#[test]
+fn function_can_reference_a_later_global() {
+    let source = "
+        fun show() { print later; }
+        var later = \"defined after\";
+        show();
+    ";
+    assert_eq!(run(source), "defined after\n");
+}
+
+#[test]
+fn return_inside_if_inside_while_leaves_only_the_function() {
+    let source = "
+        fun find(limit) {
+            var i = 0;
+            while (true) {
+                {
+                    if (i == limit) {
+                        return i * 10;
+                    }
+                }
+                i = i + 1;
+            }
+        }
+        for (var n = 0; n < 3; n = n + 1) {
+            print find(n);
+        }
+        print \"after\";
+    ";
+    assert_eq!(run(source), "0\n10\n20\nafter\n");
+}
+
+#[test]
+fn return_from_nested_loops_in_recursive_calls() {
+    let source = "
+        fun depth(n) {
+            for (var i = 0; i < 10; i = i + 1) {
+                while (true) {
+                    if (n == 0) return 0;
+                    return 1 + depth(n - 1);
+                }
+            }
+            return -1;
+        }
+        print depth(20);
+    ";
+    assert_eq!(run(source), "20\n");
+}
+
+#[test]
+fn return_without_value_inside_loop_returns_nil() {
+    let source = "fun f() { while (true) { if (true) return; } } print f();";
+    assert_eq!(run(source), "nil\n");
+}
+
+#[test]
+fn same_name_and_arity_share_a_signature() {
+    let source = "
+        var first;
+        { fun f(a) {} first = f; }
+        fun f(b) {}
+        print signature(first) == signature(f);
+        print signature(first);
+    ";
+    assert_eq!(run(source), "true\nfn:f:1\n");
+}
+
+#[test]
+fn native_signatures_include_the_name() {
+    let source =
+        "print signature(abs); print signature(round); print signature(abs) == signature(len);";
+    assert_eq!(run(source), "native:abs:1\nnative:round:1-2\nfalse\n");
+}
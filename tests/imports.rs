@@ -0,0 +1,94 @@
+use std::{
+    fs,
+    process::{Command, Output},
+};
+
+/// Writes `files` into a fresh directory and runs its `main.lox`.
+fn run_files(name: &str, files: &[(&str, &str)]) -> Output {
+    let dir = std::env::temp_dir().join(format!("lox-imports-{}-{name}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    for (file, source) in files {
+        fs::write(dir.join(file), source).unwrap();
+    }
+    Command::new(env!("CARGO_BIN_EXE_interpreter-starter-rust"))
+        .arg("run")
+        .arg(dir.join("main.lox"))
+        .output()
+        .unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+fn stderr(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stderr).into_owned()
+}
+
+#[test]
+fn imported_function_is_callable() {
+    let output = run_files(
+        "function",
+        &[
+            ("main.lox", "import \"lib.lox\";\nprint double(21);\n"),
+            ("lib.lox", "export fun double(x) { return x * 2; }\n"),
+        ],
+    );
+    assert_eq!(stdout(&output), "42\n");
+    assert!(output.status.success());
+}
+
+#[test]
+fn import_cycle_runs_each_file_once() {
+    let output = run_files(
+        "cycle",
+        &[
+            ("main.lox", "import \"a.lox\";\nprint \"main\";\n"),
+            ("a.lox", "import \"b.lox\";\nprint \"a\";\n"),
+            ("b.lox", "import \"a.lox\";\nprint \"b\";\n"),
+        ],
+    );
+    assert_eq!(stdout(&output), "b\na\nmain\n");
+}
+
+#[test]
+fn missing_file_names_the_import() {
+    let output = run_files("missing", &[("main.lox", "import \"nope.lox\";\n")]);
+    assert_eq!(output.status.code(), Some(70));
+    assert!(stderr(&output).starts_with("Could not import 'nope.lox'"));
+}
+
+#[test]
+fn only_exported_names_are_visible() {
+    let lib = "fun helper(x) { return x * 2; }\nexport fun double(x) { return helper(x); }\n";
+    let main = "import \"lib.lox\";\nprint double(4);\nprint isDefined(\"helper\");\nhelper(1);\n";
+    let output = run_files("private", &[("main.lox", main), ("lib.lox", lib)]);
+    assert_eq!(stdout(&output), "8\nfalse\n");
+    assert_eq!(stderr(&output), "Undefined variable 'helper'.\n[line 4]\n");
+    assert_eq!(output.status.code(), Some(70));
+}
+
+#[test]
+fn file_without_exports_shares_nothing() {
+    let main = "import \"lib.lox\";\nprint isDefined(\"value\");\n";
+    let output = run_files(
+        "no-exports",
+        &[("main.lox", main), ("lib.lox", "var value = 1;\n")],
+    );
+    assert_eq!(stdout(&output), "false\n");
+}
+
+#[test]
+fn exported_variable_is_shared_with_the_importer() {
+    let lib = "export var count = 0;\nexport fun inc() { count = count + 1; }\n";
+    let main =
+        "import \"lib.lox\";\ninc();\ninc();\nprint count;\ncount = 10;\ninc();\nprint count;\n";
+    let output = run_files("shared", &[("main.lox", main), ("lib.lox", lib)]);
+    assert_eq!(stdout(&output), "2\n11\n");
+}
+
+#[test]
+fn export_inside_a_block_is_a_parse_error() {
+    let output = run_files("nested", &[("main.lox", "{ export var a = 1; }\n")]);
+    assert_eq!(output.status.code(), Some(65));
+}
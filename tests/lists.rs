@@ -0,0 +1,342 @@
+use interpreter_starter_rust::interpret_to_string;
+
+fn run(source: &str) -> String {
+    interpret_to_string(source).unwrap()
+}
+
+fn run_err(source: &str) -> String {
+    interpret_to_string(source).unwrap_err().to_string()
+}
+
+#[test]
+fn list_literals_index_and_assign() {
+    let source = "var xs = [1, 2, \"three\"]; xs[1] = 5; print xs[0]; print xs;";
+    assert_eq!(run(source), "1\n[1, 5, three]\n");
+}
+
+#[test]
+fn index_past_the_end_is_an_error() {
+    assert_eq!(
+        run_err("var xs = [1, 2, 3];\nprint xs[3];"),
+        "List index out of range.\n[line 2]"
+    );
+    assert_eq!(
+        run_err("var xs = [];\nxs[0] = 1;"),
+        "List index out of range.\n[line 2]"
+    );
+}
+
+#[test]
+fn negative_index_past_the_start_is_an_error() {
+    assert_eq!(
+        run_err("var xs = [1, 2, 3];\nprint xs[-4];"),
+        "List index out of range.\n[line 2]"
+    );
+}
+
+#[test]
+fn fractional_index_is_an_error() {
+    assert_eq!(
+        run_err("print [1, 2][0.5];"),
+        "List index must be an integer.\n[line 1]"
+    );
+}
+
+#[test]
+fn lists_compare_by_contents() {
+    assert_eq!(run("print [1, 2] == [1, 2];"), "true\n");
+    assert_eq!(run("print [1, 2] == [2, 1];"), "false\n");
+    assert_eq!(run("print [1] == [1, 2];"), "false\n");
+    assert_eq!(
+        run("print [[1, [2]], {\"a\": [3]}] == [[1, [2]], {\"a\": [3]}];"),
+        "true\n"
+    );
+    assert_eq!(run("print [[1]] == [[2]];"), "false\n");
+}
+
+#[test]
+fn functions_in_lists_still_compare_by_identity() {
+    let source = "fun f() {} fun g() {} print [f] == [f]; print [f] == [g];";
+    assert_eq!(run(source), "true\nfalse\n");
+}
+
+#[test]
+fn cyclic_lists_compare_without_recursing_forever() {
+    let source = "
+        var a = [1, nil]; a[1] = a;
+        var b = [1, nil]; b[1] = b;
+        print a == b;
+        print a == a;
+        print hash(a) == hash(a);
+    ";
+    assert_eq!(run(source), "true\ntrue\ntrue\n");
+}
+
+#[test]
+fn equal_lists_hash_alike() {
+    assert_eq!(run("print hash([1, [2]]) == hash([1, [2]]);"), "true\n");
+    assert_eq!(run("print hash([[1], 2]) == hash([[1, 2]]);"), "false\n");
+}
+
+#[test]
+fn cycles_unrolled_differently_hash_alike() {
+    let source = "
+        var a = [nil];
+        a[0] = a;
+        var b = [nil];
+        var c = [b];
+        b[0] = c;
+        print a == b;
+        print hash(a) == hash(b);
+        var d = [1, nil];
+        d[1] = d;
+        var e = [1, [1, nil]];
+        e[1][1] = e;
+        print d == e;
+        print hash(d) == hash(e);
+    ";
+    assert_eq!(run(source), "true\ntrue\ntrue\ntrue\n");
+}
+
+#[test]
+fn negative_index_counts_from_the_end() {
+    assert_eq!(
+        run("var xs = [1, 2, 3]; print xs[-1]; print xs[-3];"),
+        "3\n1\n"
+    );
+    assert_eq!(
+        run("var xs = [1, 2, 3]; xs[-1] = 9; print xs;"),
+        "[1, 2, 9]\n"
+    );
+}
+
+#[test]
+fn slice_takes_every_step_item() {
+    assert_eq!(run("print slice([0, 1, 2, 3, 4], 0, 4, 2);"), "[0, 2]\n");
+    assert_eq!(run("print slice([0, 1, 2, 3, 4], 1, 5);"), "[1, 2, 3, 4]\n");
+    assert_eq!(run("print slice([0, 1, 2, 3, 4], -2, 5);"), "[3, 4]\n");
+    assert_eq!(run("print slice([0, 1, 2], 2, 1);"), "[]\n");
+}
+
+#[test]
+fn slice_errors() {
+    assert_eq!(
+        run_err("print slice([1, 2], 0, 2, 0);"),
+        "Slice step must be a positive integer.\n[line 1]"
+    );
+    assert_eq!(
+        run_err("print slice([1, 2], 0, 3);"),
+        "Slice bound out of range.\n[line 1]"
+    );
+    assert_eq!(
+        run_err("print slice([1, 2], -3, 2);"),
+        "Slice bound out of range.\n[line 1]"
+    );
+    assert_eq!(
+        run_err("print slice(\"ab\", 0, 1);"),
+        "Argument must be a list."
+    );
+}
+
+#[test]
+fn push_is_seen_through_every_alias() {
+    let source = "var a = [1]; var b = a; push(b, 2); print a; print len(a);";
+    assert_eq!(run(source), "[1, 2]\n2\n");
+}
+
+#[test]
+fn pop_returns_the_last_item() {
+    assert_eq!(run("var xs = [1, 2]; print pop(xs); print xs;"), "2\n[1]\n");
+    assert_eq!(
+        run_err("pop([]);"),
+        "Cannot pop from an empty list.\n[line 1]"
+    );
+}
+
+#[test]
+fn insert_and_remove_shift_items() {
+    let source = "
+        var xs = [1, 3];
+        insert(xs, 1, 2);
+        insert(xs, 3, 4);
+        insert(xs, 0, 0);
+        print xs;
+        print remove(xs, 0);
+        print remove(xs, -1);
+        print xs;
+    ";
+    assert_eq!(run(source), "[0, 1, 2, 3, 4]\n0\n4\n[1, 2, 3]\n");
+}
+
+#[test]
+fn mutation_natives_check_arguments() {
+    assert_eq!(
+        run_err("insert([1], 3, 0);"),
+        "List index out of range.\n[line 1]"
+    );
+    assert_eq!(
+        run_err("remove([1], 1);"),
+        "List index out of range.\n[line 1]"
+    );
+    assert_eq!(
+        run_err("remove([1], 0.5);"),
+        "List index must be an integer.\n[line 1]"
+    );
+    assert_eq!(run_err("push(\"a\", 1);"), "Argument must be a list.");
+}
+
+#[test]
+fn sort_orders_numbers_and_strings_in_place() {
+    let source = "
+        var xs = [3, 1, 2];
+        print sort(xs);
+        print xs;
+        var words = [\"pear\", \"apple\", \"fig\"];
+        sort(words);
+        print words;
+    ";
+    assert_eq!(run(source), "nil\n[1, 2, 3]\n[apple, fig, pear]\n");
+}
+
+#[test]
+fn sort_uses_a_comparator() {
+    let source = "
+        fun descending(a, b) { return b - a; }
+        fun byFirst(a, b) { return a[0] - b[0]; }
+        var xs = [1, 3, 2];
+        sort(xs, descending);
+        print xs;
+        var pairs = [[2, \"a\"], [1, \"b\"], [2, \"c\"], [1, \"d\"]];
+        sort(pairs, byFirst);
+        print pairs;
+    ";
+    assert_eq!(run(source), "[3, 2, 1]\n[[1, b], [1, d], [2, a], [2, c]]\n");
+}
+
+#[test]
+fn sort_rejects_bad_items_and_comparators() {
+    assert_eq!(
+        run_err("sort([1, \"a\"]);"),
+        "Without a comparator, sort needs all numbers or all strings."
+    );
+    assert_eq!(run_err("sort([1, 0/0]);"), "Cannot compare NaN.");
+    assert_eq!(
+        run_err("fun one(a) { return 0; } sort([1, 2], one);"),
+        "Comparator must be a function of two arguments."
+    );
+    let source = "
+        fun text(a, b) { return \"x\"; }
+        var xs = [2, 1];
+        sort(xs, text);
+    ";
+    assert_eq!(run_err(source), "Comparator must return a number.");
+}
+
+#[test]
+fn deep_equal_compares_nested_lists() {
+    let source = "
+        print deepEqual([1, [2, [\"x\", nil]]], [1, [2, [\"x\", nil]]]);
+        print deepEqual([1, [2]], [1, [3]]);
+        print deepEqual([1], 1);
+    ";
+    assert_eq!(run(source), "true\nfalse\nfalse\n");
+}
+
+#[test]
+fn deep_equal_terminates_on_cycles() {
+    let source = "
+        var a = [1];
+        push(a, a);
+        var b = [1];
+        push(b, b);
+        print deepEqual(a, b);
+        var c = [2];
+        push(c, c);
+        print deepEqual(a, c);
+    ";
+    assert_eq!(run(source), "true\nfalse\n");
+}
+
+#[test]
+fn sum_and_product_fold_numbers() {
+    let source = "
+        print sum([]);
+        print product([]);
+        print sum([1, 2, 3.5]);
+        print product([2, 3, 4]);
+    ";
+    assert_eq!(run(source), "0\n1\n6.5\n24\n");
+}
+
+#[test]
+fn sum_and_product_reject_non_numbers() {
+    assert_eq!(run_err("sum([1, \"2\"]);"), "List items must be numbers.");
+    assert_eq!(run_err("product([nil]);"), "List items must be numbers.");
+    assert_eq!(run_err("sum(1);"), "Argument must be a list.");
+}
+
+#[test]
+fn sum_handles_a_large_list() {
+    let source = "
+        var xs = [];
+        for (var i = 1; i <= 100000; i = i + 1) push(xs, i);
+        print sum(xs);
+        print product(xs) > 0;
+    ";
+    assert_eq!(run(source), "5000050000\ntrue\n");
+}
+
+#[test]
+fn lists_holding_themselves_print_a_marker() {
+    let source = "
+        var a = [nil];
+        a[0] = a;
+        print a;
+        var b = [1];
+        push(b, b);
+        print repr(b);
+        print str(b);
+    ";
+    assert_eq!(run(source), "[[...]]\n[1.0, [...]]\n[1, [...]]\n");
+}
+
+#[test]
+fn shared_lists_that_are_not_cycles_print_in_full() {
+    let source = "
+        var inner = [1];
+        print [inner, inner];
+    ";
+    assert_eq!(run(source), "[[1], [1]]\n");
+}
+
+#[test]
+fn sort_survives_an_inconsistent_comparator() {
+    let source = "
+        var k = 0;
+        fun flaky(a, b) {
+            k = k + 1;
+            if (k < 3) return 1;
+            k = 0;
+            return -1;
+        }
+        var xs = [];
+        for (var i = 0; i < 50; i = i + 1) push(xs, i);
+        sort(xs, flaky);
+        print len(xs);
+        print sum(xs);
+    ";
+    assert_eq!(run(source), "50\n1225\n");
+}
+
+#[test]
+fn keys_and_values_of_a_list() {
+    let source = "
+        var xs = [\"a\", \"b\"];
+        print keys(xs);
+        var copy = values(xs);
+        push(copy, \"c\");
+        print copy;
+        print xs;
+    ";
+    assert_eq!(run(source), "[0, 1]\n[a, b, c]\n[a, b]\n");
+}
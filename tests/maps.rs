@@ -0,0 +1,174 @@
+use interpreter_starter_rust::interpret_to_string;
+
+fn run(source: &str) -> String {
+    interpret_to_string(source).unwrap()
+}
+
+fn run_err(source: &str) -> String {
+    interpret_to_string(source).unwrap_err().to_string()
+}
+
+#[test]
+fn map_literals_lookup_and_assign() {
+    let source = r#"var m = {"b": 2, "a": 1}; m["c"] = 3; m["a"] = 0; print m["b"]; print m;"#;
+    assert_eq!(run(source), "2\n{a: 0, b: 2, c: 3}\n");
+}
+
+#[test]
+fn missing_key_reads_nil() {
+    assert_eq!(run(r#"var m = {"a": 1}; print m["b"];"#), "nil\n");
+}
+
+#[test]
+fn number_and_string_keys_are_separate() {
+    let source = r#"
+        var m = {1: "number", "1": "string"};
+        print m[1];
+        print m["1"];
+        print len(m);
+        print m;
+    "#;
+    assert_eq!(run(source), "number\nstring\n2\n{1: number, 1: string}\n");
+    assert_eq!(run(r#"var m = {1: "one"}; print m["1"];"#), "nil\n");
+    assert_eq!(run(r#"var m = {-0: "zero"}; print m[0];"#), "zero\n");
+}
+
+#[test]
+fn number_keys_stay_numbers() {
+    let source = r#"
+        for (var k in {2: "b", 1: "a"}) print k + 1;
+        print keys({1: "a"})[0] + 1;
+    "#;
+    assert_eq!(run(source), "2\n3\n2\n");
+}
+
+#[test]
+fn bad_key_type_is_an_error() {
+    assert_eq!(
+        run_err("var m = {true: 1};"),
+        "Map keys must be strings or numbers."
+    );
+    assert_eq!(
+        run_err("var m = {}; print m[nil];"),
+        "Map keys must be strings or numbers."
+    );
+    assert_eq!(
+        run_err("var m = {}; m[0/0] = 1;"),
+        "Map keys must be strings or numbers."
+    );
+}
+
+#[test]
+fn empty_braces_are_an_empty_map() {
+    assert_eq!(run("print {};"), "{}\n");
+}
+
+#[test]
+fn get_or_returns_present_value() {
+    assert_eq!(run(r#"var m = {"a": 1}; print getOr(m, "a", 0);"#), "1\n");
+    assert_eq!(
+        run(r#"var m = {"a": nil}; print getOr(m, "a", 0);"#),
+        "nil\n"
+    );
+}
+
+#[test]
+fn get_or_returns_default_for_absent_key() {
+    assert_eq!(run(r#"print getOr({}, "a", "default");"#), "default\n");
+    assert_eq!(run(r#"print getOr({"1": "one"}, 1, "none");"#), "none\n");
+}
+
+#[test]
+fn get_or_rejects_non_maps_and_bad_keys() {
+    assert_eq!(run_err(r#"getOr([1], 0, nil);"#), "Argument must be a map.");
+    assert_eq!(
+        run_err("getOr({}, true, nil);"),
+        "Map keys must be strings or numbers."
+    );
+}
+
+#[test]
+fn maps_compare_by_contents() {
+    assert_eq!(
+        run(r#"print {"a": 1, "b": [2]} == {"b": [2], "a": 1};"#),
+        "true\n"
+    );
+    assert_eq!(run(r#"print {"a": 1} == {"a": 2};"#), "false\n");
+    assert_eq!(run(r#"print {"a": 1} == {"b": 1};"#), "false\n");
+    assert_eq!(run(r#"print {} == {};"#), "true\n");
+    assert_eq!(run(r#"print hash({"a": 1}) == hash({"a": 1});"#), "true\n");
+    let source = r#"
+        var a = {"next": nil};
+        a["next"] = a;
+        var b = {"next": {"next": nil}};
+        b["next"]["next"] = b;
+        print a == b;
+        print hash(a) == hash(b);
+    "#;
+    assert_eq!(run(source), "true\ntrue\n");
+}
+
+#[test]
+fn deep_equal_tells_maps_apart() {
+    let source = "
+        print deepEqual({\"a\": [1], \"b\": 2}, {\"b\": 2, \"a\": [1]});
+        print deepEqual({\"a\": 1}, {\"a\": 2});
+        print deepEqual({\"a\": 1}, {\"b\": 1});
+        print deepEqual({\"a\": 1}, {\"a\": 1, \"b\": 2});
+    ";
+    assert_eq!(run(source), "true\nfalse\nfalse\nfalse\n");
+}
+
+#[test]
+fn maps_holding_themselves_print_a_marker() {
+    let source = "
+        var m = {\"a\": 1};
+        m[\"self\"] = m;
+        print m;
+        print str(m);
+        print repr(m);
+    ";
+    assert_eq!(
+        run(source),
+        "{a: 1, self: {...}}\n{a: 1, self: {...}}\n{\"a\": 1.0, \"self\": {...}}\n"
+    );
+}
+
+#[test]
+fn keys_and_values_follow_key_order() {
+    let source = r#"
+        var m = {"b": 2, "c": 3, "a": 1, 10: "ten"};
+        print keys(m);
+        print values(m);
+        m["a"] = 0;
+        print values(m);
+        print keys({});
+    "#;
+    assert_eq!(
+        run(source),
+        "[10, a, b, c]\n[ten, 1, 2, 3]\n[ten, 0, 2, 3]\n[]\n"
+    );
+}
+
+#[test]
+fn len_counts_strings_lists_and_maps() {
+    let source = r#"
+        print len("héllo");
+        print len([1, 2, 3]);
+        print len({"a": 1, "b": 2});
+    "#;
+    assert_eq!(run(source), "5\n3\n2\n");
+}
+
+#[test]
+fn container_natives_reject_other_types() {
+    assert_eq!(
+        run_err("len(3);"),
+        "Only strings, lists and maps have a length."
+    );
+    assert_eq!(run_err("keys(3);"), "Argument must be a list or map.");
+    assert_eq!(
+        run_err(r#"values("ab");"#),
+        "Argument must be a list or map."
+    );
+}
@@ -0,0 +1,196 @@
+use interpreter_starter_rust::interpret_to_string;
+
+fn run(source: &str) -> String {
+    interpret_to_string(source).unwrap()
+}
+
+fn run_err(source: &str) -> String {
+    interpret_to_string(source).unwrap_err().to_string()
+}
+
+#[test]
+fn round_to_decimal_places() {
+    assert_eq!(run("print round(3.14159, 2) == 3.14;"), "true\n");
+    assert_eq!(run("print round(-2.675, 1);"), "-2.7\n");
+    assert_eq!(run("print round(2.5, 0);"), "3\n");
+    assert_eq!(run("print round(-2.5);"), "-3\n");
+}
+
+#[test]
+fn round_with_huge_digits_keeps_the_value() {
+    assert_eq!(run("print round(1.5, 400);"), "1.5\n");
+    assert_eq!(
+        run("var x = 123456789.125; print round(x, 300) == x;"),
+        "true\n"
+    );
+}
+
+#[test]
+fn round_rejects_bad_digits() {
+    assert_eq!(
+        run_err("round(1.5, -1);"),
+        "Digits must be a non-negative integer."
+    );
+    assert_eq!(
+        run_err("round(1.5, 0.5);"),
+        "Digits must be a non-negative integer."
+    );
+}
+
+#[test]
+fn floor_and_trunc_differ_on_negatives() {
+    assert_eq!(run("print floor(-2.5); print trunc(-2.5);"), "-3\n-2\n");
+    assert_eq!(run("print floor(2.5); print trunc(2.5);"), "2\n2\n");
+}
+
+#[test]
+fn str_is_raw_and_repr_is_quoted() {
+    let source = "
+        print str(\"a\");
+        print repr(\"a\");
+        print str(\"a\nb\");
+        print repr(\"a\nb\tc\\d\");
+        print repr(1);
+        print repr(nil);
+        print repr(\"nil\");
+    ";
+    let expected = [
+        "a",
+        r#""a""#,
+        "a",
+        "b",
+        r#""a\nb\tc\\d""#,
+        "1.0",
+        "nil",
+        r#""nil""#,
+    ];
+    assert_eq!(run(source), expected.join("\n") + "\n");
+}
+
+#[test]
+fn repr_escapes_strings_inside_lists_and_maps() {
+    let source = "
+        print repr([\"a\nb\", 2, \"c\\d\", nil]);
+        print repr({\"k\ney\": \"v\tal\", 1: \"one\"});
+        print str([\"a\", {\"b\": \"c\"}]);
+    ";
+    let expected = [
+        r#"["a\nb", 2.0, "c\\d", nil]"#,
+        r#"{1.0: "one", "k\ney": "v\tal"}"#,
+        "[a, {b: c}]",
+    ];
+    assert_eq!(run(source), expected.join("\n") + "\n");
+}
+
+#[test]
+fn repr_and_str_stop_at_cycles() {
+    let source = r#"
+        var xs = ["x"];
+        push(xs, xs);
+        var m = {};
+        m["m"] = m;
+        print repr(xs);
+        print str(xs);
+        print repr(m);
+    "#;
+    assert_eq!(run(source), "[\"x\", [...]]\n[x, [...]]\n{\"m\": {...}}\n");
+}
+
+#[test]
+fn parse_int_reads_signs_whitespace_and_radix() {
+    let source = r#"
+        print parseInt("42");
+        print parseInt("  -17 ");
+        print parseInt("+8");
+        print parseInt("ff", 16);
+        print parseInt("-101", 2);
+        print parseInt("z", 36);
+    "#;
+    assert_eq!(run(source), "42\n-17\n8\n255\n-5\n35\n");
+}
+
+#[test]
+fn parse_int_returns_nil_for_invalid_input() {
+    let source = r#"
+        print parseInt("");
+        print parseInt("12abc");
+        print parseInt("1.5");
+        print parseInt("- 1");
+        print parseInt("2", 2);
+    "#;
+    assert_eq!(run(source), "nil\nnil\nnil\nnil\nnil\n");
+}
+
+#[test]
+fn parse_int_rejects_bad_arguments() {
+    let message = "Radix must be an integer between 2 and 36.";
+    assert_eq!(run_err(r#"parseInt("1", 1);"#), message);
+    assert_eq!(run_err(r#"parseInt("1", 37);"#), message);
+    assert_eq!(run_err(r#"parseInt("1", 2.5);"#), message);
+    assert_eq!(run_err("parseInt(1);"), "Argument must be a string.");
+}
+
+#[test]
+fn parse_float_reads_signs_and_whitespace() {
+    let source = r#"
+        print parseFloat("3.25");
+        print parseFloat(" -0.5  ");
+        print parseFloat("+2");
+    "#;
+    assert_eq!(run(source), "3.25\n-0.5\n2\n");
+}
+
+#[test]
+fn parse_float_returns_nil_for_invalid_input() {
+    let source = r#"
+        print parseFloat("abc");
+        print parseFloat("1.2.3");
+        print parseFloat("");
+        print parseFloat("inf");
+        print parseFloat("NaN");
+    "#;
+    assert_eq!(run(source), "nil\nnil\nnil\nnil\nnil\n");
+}
+
+#[test]
+fn gcd_and_lcm_of_positive_integers() {
+    assert_eq!(run("print gcd(12, 18); print lcm(4, 6);"), "6\n12\n");
+}
+
+#[test]
+fn gcd_and_lcm_with_zero() {
+    let source = "
+        print gcd(0, 5);
+        print gcd(5, 0);
+        print gcd(0, 0);
+        print lcm(0, 5);
+        print lcm(0, 0);
+    ";
+    assert_eq!(run(source), "5\n5\n0\n0\n0\n");
+}
+
+#[test]
+fn gcd_and_lcm_are_never_negative() {
+    let source = "
+        print gcd(-12, 18);
+        print gcd(-12, -18);
+        print lcm(-4, 6);
+        print lcm(-4, -6);
+    ";
+    assert_eq!(run(source), "6\n6\n12\n12\n");
+}
+
+#[test]
+fn gcd_and_lcm_reject_non_integers() {
+    let message = "Argument must be an integer.";
+    assert_eq!(run_err("gcd(1.5, 3);"), message);
+    assert_eq!(run_err("lcm(4, 0.25);"), message);
+    assert_eq!(run_err("gcd(\"4\", 2);"), message);
+    assert_eq!(run_err("lcm(1/0, 2);"), message);
+}
+
+#[test]
+fn sign_and_abs() {
+    let source = "print sign(-3); print sign(0); print sign(2.5); print abs(-2.5);";
+    assert_eq!(run(source), "-1\n0\n1\n2.5\n");
+}
@@ -0,0 +1,165 @@
+use anyhow::Result;
+use std::{cell::RefCell, rc::Rc};
+
+use interpreter_starter_rust::{Interpreter, Literal, Parser, Scanner, Truthiness};
+
+fn interpret(interpreter: &mut Interpreter, source: &str) -> Result<Literal> {
+    let tokens = Scanner::new(source).scan_tokens();
+    let statements = Parser::new(&tokens).parse()?;
+    interpreter.interpret(&statements)
+}
+
+/// Runs `source`, then evaluates `expression` in the same interpreter.
+fn value_after(interpreter: &mut Interpreter, source: &str, expression: &str) -> Literal {
+    interpret(interpreter, source).unwrap();
+    interpreter.eval_expression(expression).unwrap()
+}
+
+fn strings(values: &[&str]) -> Literal {
+    let items = values
+        .iter()
+        .map(|value| Literal::String(value.to_string()))
+        .collect();
+    Literal::List(Rc::new(RefCell::new(items)))
+}
+
+const TRUTHINESS: &str = "
+    var zero = \"falsy\";
+    if (0) zero = \"truthy\";
+    var empty = \"falsy\";
+    if (\"\") empty = \"truthy\";
+";
+
+#[test]
+fn zero_and_empty_string_are_truthy_by_default() {
+    let mut interpreter = Interpreter::new();
+    assert_eq!(
+        value_after(&mut interpreter, TRUTHINESS, "[zero, empty]"),
+        strings(&["truthy", "truthy"])
+    );
+    assert_eq!(
+        interpreter.eval_expression("!0").unwrap(),
+        Literal::Boolean(false)
+    );
+}
+
+#[test]
+fn zero_and_empty_string_are_falsy_in_c_mode() {
+    let mut interpreter = Interpreter::new();
+    interpreter.set_truthiness(Truthiness::C);
+    assert_eq!(
+        value_after(&mut interpreter, TRUTHINESS, "[zero, empty]"),
+        strings(&["falsy", "falsy"])
+    );
+    assert_eq!(
+        interpreter.eval_expression("!0").unwrap(),
+        Literal::Boolean(true)
+    );
+    assert_eq!(
+        interpreter.eval_expression("\"\" or \"default\"").unwrap(),
+        Literal::String("default".to_string())
+    );
+    assert_eq!(
+        interpreter
+            .eval_expression("if 1 then \"one\" else \"zero\"")
+            .unwrap(),
+        Literal::String("one".to_string())
+    );
+}
+
+const CALL_BEFORE_DECLARE: &str = "
+    var result;
+    {
+        result = later();
+        fun later() { return \"hoisted\"; }
+    }
+";
+
+#[test]
+fn hoisting_lets_a_block_call_a_later_function() {
+    let mut interpreter = Interpreter::new();
+    interpreter.set_hoist_functions(true);
+    assert_eq!(
+        value_after(&mut interpreter, CALL_BEFORE_DECLARE, "result"),
+        Literal::String("hoisted".to_string())
+    );
+}
+
+#[test]
+fn hoisting_applies_to_function_bodies() {
+    let mut interpreter = Interpreter::new();
+    interpreter.set_hoist_functions(true);
+    let source = "
+        fun outer() {
+            return inner(2);
+            fun inner(n) { return n * 10; }
+        }
+    ";
+    assert_eq!(
+        value_after(&mut interpreter, source, "outer()"),
+        Literal::Number(20.0)
+    );
+}
+
+#[test]
+fn without_hoisting_a_later_function_is_undefined() {
+    let mut interpreter = Interpreter::new();
+    let error = interpret(&mut interpreter, CALL_BEFORE_DECLARE).unwrap_err();
+    assert_eq!(error.to_string(), "Undefined variable 'later'.\n[line 4]");
+}
+
+#[test]
+fn nil_propagates_through_arithmetic_when_enabled() {
+    let mut interpreter = Interpreter::new();
+    interpreter.set_propagate_nil(true);
+    for expression in [
+        "nil + 5",
+        "5 - nil",
+        "nil * nil",
+        "10 / nil",
+        "(nil + 1) * 2",
+    ] {
+        assert_eq!(
+            interpreter.eval_expression(expression).unwrap(),
+            Literal::Nil,
+            "{expression}"
+        );
+    }
+    // Comparisons are not arithmetic and still need numbers
+    assert!(interpreter.eval_expression("nil < 1").is_err());
+}
+
+#[test]
+fn nil_arithmetic_is_an_error_by_default() {
+    let mut interpreter = Interpreter::new();
+    for expression in ["nil + 5", "5 - nil", "nil * nil", "10 / nil"] {
+        assert!(
+            interpreter.eval_expression(expression).is_err(),
+            "{expression}"
+        );
+    }
+}
+
+#[test]
+fn native_call_hook_sees_each_native_call() {
+    let calls = Rc::new(RefCell::new(vec![]));
+    let mut interpreter = Interpreter::new();
+    let recorded = calls.clone();
+    interpreter.on_native_call(move |name, arg_count| {
+        recorded.borrow_mut().push((name.to_string(), arg_count));
+    });
+    let source = "
+        fun twice() { clock(); clock(); }
+        twice();
+        len(\"abc\");
+    ";
+    interpret(&mut interpreter, source).unwrap();
+    assert_eq!(
+        *calls.borrow(),
+        [
+            ("clock".to_string(), 0),
+            ("clock".to_string(), 0),
+            ("len".to_string(), 1),
+        ]
+    );
+}
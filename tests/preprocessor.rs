@@ -0,0 +1,41 @@
+use std::collections::HashSet;
+
+use interpreter_starter_rust::{interpret_to_string, preprocess};
+
+const SOURCE: &str = "print \"always\";\n@if DEBUG\nprint \"debug\";\n@endif\nprint \"done\";\n";
+
+fn run_with(source: &str, features: &[&str]) -> String {
+    let features: HashSet<String> = features.iter().map(|f| f.to_string()).collect();
+    interpret_to_string(&preprocess(source, &features).unwrap()).unwrap()
+}
+
+#[test]
+fn enabled_block_runs() {
+    assert_eq!(run_with(SOURCE, &["DEBUG"]), "always\ndebug\ndone\n");
+}
+
+#[test]
+fn disabled_block_is_skipped() {
+    assert_eq!(run_with(SOURCE, &[]), "always\ndone\n");
+}
+
+#[test]
+fn skipped_lines_keep_line_numbers() {
+    let source = "@if DEBUG\nprint 1;\n@endif\nprint missing;\n";
+    let output = preprocess(source, &HashSet::new()).unwrap();
+    let error = interpret_to_string(&output).unwrap_err().to_string();
+    assert!(error.ends_with("[line 4]"), "{error}");
+}
+
+#[test]
+fn directives_inside_strings_are_text() {
+    let source = "print \"a\n@if DEBUG\nb\";\n";
+    assert_eq!(preprocess(source, &HashSet::new()).unwrap(), source);
+    assert_eq!(run_with(source, &[]), "a\n@if DEBUG\nb\n");
+}
+
+#[test]
+fn unterminated_block_is_an_error() {
+    let error = preprocess("@if DEBUG\nprint 1;\n", &HashSet::new()).unwrap_err();
+    assert_eq!(error.to_string(), "[line 1] Error: Unterminated '@if'.");
+}
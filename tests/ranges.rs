@@ -0,0 +1,38 @@
+use interpreter_starter_rust::interpret_to_string;
+
+fn run(source: &str) -> String {
+    interpret_to_string(source).unwrap()
+}
+
+fn run_err(source: &str) -> String {
+    interpret_to_string(source).unwrap_err().to_string()
+}
+
+#[test]
+fn exclusive_range_iterates_up_to_end() {
+    assert_eq!(run("for (var i in 1..4) print i;"), "1\n2\n3\n");
+}
+
+#[test]
+fn inclusive_range_includes_end() {
+    assert_eq!(run("for (var i in 1..=3) print i;"), "1\n2\n3\n");
+    assert_eq!(run("print 3 in 1..=3; print 3 in 1..3;"), "true\nfalse\n");
+}
+
+#[test]
+fn iterating_past_safe_integers_is_an_error() {
+    let message = "Range bounds must be within 2^53 - 1 of zero to iterate.";
+    assert_eq!(
+        run_err("for (var i in 9007199254740992..9007199254740994) print i;"),
+        message
+    );
+    assert_eq!(run_err("for (var i in 0..1/0) print i;"), message);
+}
+
+#[test]
+fn range_ending_at_the_safe_limit_terminates() {
+    assert_eq!(
+        run("for (var i in 9007199254740990..=9007199254740991) print i;"),
+        "9007199254740990\n9007199254740991\n"
+    );
+}
@@ -0,0 +1,69 @@
+use interpreter_starter_rust::{Interpreter, Literal, Parser, RuntimeError, Scanner};
+
+fn run_sandboxed(source: &str) -> anyhow::Result<()> {
+    let tokens = Scanner::new(source).scan_tokens();
+    let statements = Parser::new(&tokens).parse()?;
+    Interpreter::new_sandboxed()
+        .interpret(&statements)
+        .map(|_| ())
+}
+
+#[test]
+fn exit_is_undefined() {
+    let error = run_sandboxed("exit(1);").unwrap_err();
+    assert!(matches!(
+        error.downcast_ref::<RuntimeError>(),
+        Some(RuntimeError::UndefinedVariable { lexeme, .. }) if lexeme == "exit"
+    ));
+}
+
+#[test]
+fn clock_is_deterministic() {
+    run_sandboxed("assert(clock() == 0);").unwrap();
+}
+
+#[test]
+fn pure_natives_are_available() {
+    run_sandboxed("assert(abs(-2) == 2); assert(len(\"abc\") == 3);").unwrap();
+}
+
+#[test]
+fn import_is_rejected() {
+    let error = run_sandboxed("import \"other.lox\";").unwrap_err();
+    assert!(matches!(
+        error.downcast_ref::<RuntimeError>(),
+        Some(RuntimeError::ImportError { .. })
+    ));
+}
+
+#[test]
+fn reset_keeps_the_sandbox() {
+    let mut interpreter = Interpreter::new_sandboxed();
+    interpreter.reset();
+    let tokens = Scanner::new("assert(clock() == 0);").scan_tokens();
+    let statements = Parser::new(&tokens).parse().unwrap();
+    interpreter.interpret(&statements).unwrap();
+}
+
+#[test]
+fn reset_clears_globals_and_restores_natives() {
+    let mut interpreter = Interpreter::new();
+    let tokens = Scanner::new("var answer = 42; var clock = \"shadowed\";").scan_tokens();
+    let statements = Parser::new(&tokens).parse().unwrap();
+    interpreter.interpret(&statements).unwrap();
+    interpreter.reset();
+    let error = interpreter.eval_expression("answer").unwrap_err();
+    assert!(matches!(
+        error.downcast_ref::<RuntimeError>(),
+        Some(RuntimeError::UndefinedVariable { lexeme, .. }) if lexeme == "answer"
+    ));
+    assert_eq!(
+        interpreter.eval_expression("signature(clock)").unwrap(),
+        Literal::String("native:clock:0".to_string())
+    );
+    // The real clock, not the sandbox's fixed one
+    assert_eq!(
+        interpreter.eval_expression("clock() > 0").unwrap(),
+        Literal::Boolean(true)
+    );
+}
@@ -0,0 +1,161 @@
+use interpreter_starter_rust::{interpret_to_string, tokenize, Literal, Scanner, TokenType};
+
+fn identifier_lines(source: &str) -> Vec<usize> {
+    let (tokens, error) = tokenize(source);
+    assert!(!error);
+    tokens
+        .iter()
+        .filter(|token| token.token_type == TokenType::IDENTIFIER)
+        .map(|token| token.line)
+        .collect()
+}
+
+#[test]
+fn lf_line_endings_count_one_line_each() {
+    assert_eq!(identifier_lines("a\nb\n\nc"), [1, 2, 4]);
+}
+
+#[test]
+fn crlf_line_endings_count_one_line_each() {
+    assert_eq!(identifier_lines("a\r\nb\r\n\r\nc"), [1, 2, 4]);
+}
+
+#[test]
+fn bare_cr_line_endings_count_one_line_each() {
+    assert_eq!(identifier_lines("a\rb\r\rc"), [1, 2, 4]);
+}
+
+#[test]
+fn line_comments_end_at_any_line_ending() {
+    assert_eq!(identifier_lines("// x\r\na // y\rb"), [2, 3]);
+}
+
+#[test]
+fn runtime_errors_report_the_visual_line() {
+    for ending in ["\n", "\r\n", "\r"] {
+        let source = ["print 1;", "", "print missing;"].join(ending);
+        let error = interpret_to_string(&source).unwrap_err().to_string();
+        assert!(error.ends_with("[line 3]"), "{ending:?}: {error}");
+    }
+}
+
+#[test]
+fn fed_chunks_continue_line_numbers() {
+    let mut scanner = Scanner::new("var a = 1;\n");
+    scanner.scan_tokens();
+    scanner.feed("var b = 2;\nprint c;");
+    let tokens = scanner.scan_tokens();
+    let lines: Vec<(String, usize)> = tokens
+        .iter()
+        .filter(|token| token.token_type == TokenType::IDENTIFIER)
+        .map(|token| (token.lexeme.clone(), token.line))
+        .collect();
+    assert_eq!(lines, [("a".into(), 1), ("b".into(), 2), ("c".into(), 3)]);
+    assert!(!scanner.error);
+}
+
+#[test]
+fn fed_chunk_can_close_an_open_string() {
+    let mut scanner = Scanner::new("print \"abc\n");
+    scanner.set_quiet(true);
+    scanner.scan_tokens();
+    assert!(scanner.error);
+    scanner.feed("def\";\nprint x;");
+    let tokens = scanner.scan_tokens();
+    assert!(!scanner.error);
+    let summary: Vec<(TokenType, usize)> = tokens
+        .iter()
+        .map(|token| (token.token_type.clone(), token.line))
+        .collect();
+    assert_eq!(
+        summary,
+        [
+            (TokenType::PRINT, 1),
+            (TokenType::STRING, 2),
+            (TokenType::SEMICOLON, 2),
+            (TokenType::PRINT, 3),
+            (TokenType::IDENTIFIER, 3),
+            (TokenType::SEMICOLON, 3),
+            (TokenType::EOF, 3),
+        ]
+    );
+    assert_eq!(
+        tokens[1].literal,
+        Some(Literal::String("abc\ndef".to_string()))
+    );
+}
+
+#[test]
+fn new_at_starts_counting_from_the_given_line() {
+    let tokens = Scanner::new_at("\nx", 10).scan_tokens();
+    assert_eq!(tokens[0].line, 11);
+}
+
+#[test]
+fn unterminated_string_still_errors_at_end_of_input() {
+    let (_, error) = tokenize("print \"abc\ndef");
+    assert!(error);
+}
+
+fn string_literals(source: &str, escapes: bool) -> Vec<Literal> {
+    let mut scanner = Scanner::new(source);
+    scanner.set_escapes(escapes);
+    let tokens = scanner.scan_tokens();
+    assert!(!scanner.error);
+    tokens
+        .into_iter()
+        .filter(|token| token.token_type == TokenType::STRING)
+        .filter_map(|token| token.literal)
+        .collect()
+}
+
+#[test]
+fn raw_strings_keep_backslashes_that_plain_strings_decode() {
+    let source = r#""a\nb" r"a\nb""#;
+    assert_eq!(
+        string_literals(source, true),
+        [
+            Literal::String("a\nb".to_string()),
+            Literal::String(r"a\nb".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn plain_strings_are_verbatim_without_escapes() {
+    let source = r#""a\nb" r"a\nb""#;
+    assert_eq!(
+        string_literals(source, false),
+        [
+            Literal::String(r"a\nb".to_string()),
+            Literal::String(r"a\nb".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn escaped_quote_does_not_end_a_plain_string() {
+    let source = r#""say \"hi\"\\" r"C:\path\""#;
+    assert_eq!(
+        string_literals(source, true),
+        [
+            Literal::String(r#"say "hi"\"#.to_string()),
+            Literal::String(r"C:\path\".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn raw_strings_may_span_lines() {
+    assert_eq!(interpret_to_string("print r\"a\nb\";").unwrap(), "a\nb\n");
+}
+
+#[test]
+fn unterminated_raw_string_is_an_error() {
+    let mut scanner = Scanner::new("print r\"abc\ndef");
+    scanner.set_quiet(true);
+    let tokens = scanner.scan_tokens();
+    assert!(scanner.error);
+    let types: Vec<TokenType> = tokens.into_iter().map(|token| token.token_type).collect();
+    assert_eq!(types, [TokenType::PRINT, TokenType::EOF]);
+}